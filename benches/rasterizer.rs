@@ -0,0 +1,110 @@
+// rasterizer.rs
+//
+// Compara el rasterizador que junta todos los fragmentos en un Vec antes de
+// sombrearlos con el que sombrea directamente con early depth test, en un hilo y por tiles.
+//
+//     cargo bench --bench rasterizer
+
+use nalgebra_glm::Vec3;
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use graficas_lab4::{Camera, Obj, PlanetShader, RasterState, Renderer, ShaderRegistry, Uniforms, Vertex};
+use graficas_lab4::clipping::{clip_triangle, project_to_screen};
+use graficas_lab4::framebuffer::Framebuffer;
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::triangle::{triangle, Interpolation};
+
+const WIDTH: usize = 800;
+const HEIGHT: usize = 600;
+const ITERATIONS: u32 = 20;
+
+// El pipeline anterior: Vec de triángulos, luego Vec de fragmentos, luego sombreado
+fn render_collecting(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], shader: &dyn PlanetShader) {
+    let transformed: Vec<Vertex> = vertex_array.iter().map(|vertex| shader.vertex(vertex, uniforms)).collect();
+
+    let mut triangles = Vec::new();
+    for tri in transformed.chunks_exact(3) {
+        for mut clipped in clip_triangle(&tri[0], &tri[1], &tri[2]) {
+            for vertex in &mut clipped {
+                project_to_screen(vertex, &uniforms.viewport_matrix);
+            }
+            triangles.push(clipped);
+        }
+    }
+
+    let mut fragments = Vec::new();
+    for tri in &triangles {
+        fragments.extend(triangle(&tri[0], &tri[1], &tri[2], Interpolation::Perspective));
+    }
+
+    for mut fragment in fragments {
+        let x = fragment.position.x as usize;
+        let y = fragment.position.y as usize;
+        if x < framebuffer.width && y < framebuffer.height {
+            fragment.intensity = uniforms.diffuse_intensity(&fragment);
+            let color = shader.fragment(&fragment, uniforms);
+            framebuffer.set_current_color(color.to_hex());
+            framebuffer.point(x, y, fragment.depth);
+        }
+    }
+}
+
+fn time_frames(mut frame: impl FnMut()) -> Duration {
+    frame();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        frame();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mesh = Obj::load("assets/model/sphere.obj").expect("Failed to load obj").get_vertex_array();
+    let noise = Arc::new(create_generic_noise());
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+    let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+    println!("sphere.obj, {} triangles, {}x{}, {} frames", mesh.len() / 3, WIDTH, HEIGHT, ITERATIONS);
+    for name in ["rocky_planet", "gas_giant", "sun"] {
+        let shader = registry.create(name).unwrap();
+
+        let mut renderer = Renderer::new(WIDTH, HEIGHT);
+        renderer.set_camera(&camera);
+        renderer.uniforms.model_matrix = model_matrix;
+        renderer.uniforms.noise = Arc::clone(&noise);
+
+        let collecting = time_frames(|| {
+            renderer.clear();
+            render_collecting(&mut renderer.framebuffer, &renderer.uniforms, &mesh, shader.as_ref());
+            renderer.framebuffer.resolve();
+            black_box(&renderer.framebuffer.buffer);
+        });
+
+        renderer.raster.threads = 1;
+        let streaming = time_frames(|| {
+            renderer.clear();
+            renderer.draw(&mesh, model_matrix, &noise, shader.as_ref());
+            black_box(&renderer.framebuffer.buffer);
+        });
+
+        renderer.raster.threads = RasterState::default().threads.max(2);
+        let tiled = time_frames(|| {
+            renderer.clear();
+            renderer.draw(&mesh, model_matrix, &noise, shader.as_ref());
+            black_box(&renderer.framebuffer.buffer);
+        });
+
+        println!(
+            "{:<14} collecting {:>8.2?}   streaming {:>8.2?}   tiled ({} threads) {:>8.2?}",
+            name,
+            collecting,
+            streaming,
+            renderer.raster.threads,
+            tiled
+        );
+    }
+}
@@ -0,0 +1,82 @@
+// custom_shader.rs
+//
+// Registra un shader definido fuera del crate y lo renderiza sin ventana:
+//     cargo run --example custom_shader -- stripes.png
+
+use nalgebra_glm::Vec3;
+use std::env;
+use std::sync::Arc;
+
+use graficas_lab4::{Camera, Color, Fragment, Obj, ParamValue, ParameterError, PlanetShader, Renderer, ShaderParameter, ShaderRegistry, Uniforms, Vertex};
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::shaders::vertex_shader;
+use graficas_lab4::noise::create_generic_noise;
+
+const HEIGHT: usize = 0;
+
+struct StripesShader {
+    color: Color,
+    stripes: f32,
+}
+
+impl PlanetShader for StripesShader {
+    fn name(&self) -> &str {
+        "stripes"
+    }
+
+    // La altura en espacio de mundo viaja al fragment shader en el varying HEIGHT
+    fn vertex(&self, vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+        let mut transformed = vertex_shader(vertex, uniforms);
+        transformed.varyings.set_float(HEIGHT, transformed.world_position.y);
+        transformed
+    }
+
+    fn fragment(&self, fragment: &Fragment, _uniforms: &Uniforms) -> Color {
+        let height = fragment.varyings.float(HEIGHT);
+        let stripe = (height * self.stripes).sin() * 0.5 + 0.5;
+        self.color * stripe * fragment.intensity
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("color", self.color),
+            ShaderParameter::float("stripes", self.stripes),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "color" => self.color = value.as_color(name)?,
+            "stripes" => self.stripes = value.as_float(name)?,
+            _ => return Err(ParameterError::Unknown { shader: self.name().to_string(), name: name.to_string() }),
+        }
+        Ok(())
+    }
+}
+
+fn main() {
+    let output = env::args().nth(1).unwrap_or_else(|| "stripes.png".to_string());
+
+    let mut registry = ShaderRegistry::with_builtin_shaders();
+    registry.register(|| Box::new(StripesShader { color: Color::new(255, 120, 40), stripes: 40.0 }));
+
+    let shader = registry.create("stripes").expect("shader was just registered");
+    for parameter in shader.parameters() {
+        println!("{} = {:?}", parameter.name, parameter.value);
+    }
+
+    let obj = Obj::load("assets/model/sphere.obj").expect("Failed to load obj");
+    let vertex_array = obj.get_vertex_array();
+    let camera = Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+
+    let mut renderer = Renderer::new(400, 300);
+    renderer.framebuffer.set_background_color(0x333355);
+    renderer.clear();
+    renderer.set_camera(&camera);
+
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+    renderer.draw(&vertex_array, model_matrix, &Arc::new(create_generic_noise()), shader.as_ref());
+
+    renderer.framebuffer.save(&output).expect("Failed to write image");
+    println!("wrote {}", output);
+}
@@ -0,0 +1,93 @@
+// antialiasing.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+// Máximo de muestras por pixel; las máscaras de cobertura son de 8 bits
+pub const MAX_SAMPLES: usize = 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Samples {
+    X2,
+    X4,
+    X8,
+}
+
+impl Samples {
+    pub fn count(self) -> usize {
+        match self {
+            Samples::X2 => 2,
+            Samples::X4 => 4,
+            Samples::X8 => 8,
+        }
+    }
+}
+
+// MSAA sombrea una vez por pixel y guarda color/profundidad por muestra; SSAA usa las
+// mismas muestras pero ejecuta el fragment shader en cada una (más caro, sirve para comparar)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AntiAliasing {
+    #[default]
+    None,
+    Msaa(Samples),
+    Ssaa(Samples),
+}
+
+impl AntiAliasing {
+    pub const ALL: [AntiAliasing; 7] = [
+        AntiAliasing::None,
+        AntiAliasing::Msaa(Samples::X2),
+        AntiAliasing::Msaa(Samples::X4),
+        AntiAliasing::Msaa(Samples::X8),
+        AntiAliasing::Ssaa(Samples::X2),
+        AntiAliasing::Ssaa(Samples::X4),
+        AntiAliasing::Ssaa(Samples::X8),
+    ];
+
+    pub fn sample_count(self) -> usize {
+        match self {
+            AntiAliasing::None => 1,
+            AntiAliasing::Msaa(samples) | AntiAliasing::Ssaa(samples) => samples.count(),
+        }
+    }
+
+    pub fn per_sample_shading(self) -> bool {
+        matches!(self, AntiAliasing::Ssaa(_))
+    }
+
+    // Posiciones de las muestras dentro del pixel en unidades de 1/16 (patrón estándar de D3D)
+    pub fn sample_positions(self) -> &'static [(i64, i64)] {
+        match self.sample_count() {
+            2 => &[(12, 12), (4, 4)],
+            4 => &[(6, 2), (14, 6), (2, 10), (10, 14)],
+            8 => &[(9, 5), (7, 11), (13, 9), (5, 3), (3, 13), (1, 7), (11, 15), (15, 1)],
+            _ => &[(8, 8)],
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for AntiAliasing {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AntiAliasing::None => write!(f, "none"),
+            AntiAliasing::Msaa(samples) => write!(f, "msaa{}", samples.count()),
+            AntiAliasing::Ssaa(samples) => write!(f, "ssaa{}", samples.count()),
+        }
+    }
+}
+
+impl FromStr for AntiAliasing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        AntiAliasing::ALL
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("unknown anti-aliasing mode '{}', expected none, msaa2/4/8 or ssaa2/4/8", s))
+    }
+}
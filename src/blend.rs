@@ -0,0 +1,72 @@
+// blend.rs
+
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+
+// Cómo se combina el color de un fragmento con lo que ya hay en el framebuffer. Todo lo
+// que no sea Opaque se dibuja en la pasada transparente: prueba la profundidad pero no
+// la escribe, y la opacidad del fragmento (`Color::a`) escala el efecto.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendMode {
+    // Reemplaza el color y escribe la profundidad
+    #[default]
+    Opaque,
+    // Mezcla "over": dst + (src - dst) * a
+    Alpha,
+    // Suma el color (brillos, coronas)
+    Additive,
+    // Oscurece multiplicando (sombras de nubes, polvo)
+    Multiply,
+    // Aclara sin pasar del color más brillante (neblina, atmósferas)
+    Screen,
+}
+
+impl BlendMode {
+    pub const ALL: [BlendMode; 5] = [BlendMode::Opaque, BlendMode::Alpha, BlendMode::Additive, BlendMode::Multiply, BlendMode::Screen];
+
+    pub fn is_opaque(self) -> bool {
+        self == BlendMode::Opaque
+    }
+
+    // Combina `src` (el fragmento) sobre `dst` (el framebuffer); la opacidad del
+    // resultado es la de `dst`
+    pub fn apply(self, dst: Color, src: Color) -> Color {
+        let alpha = src.a();
+        let blended = match self {
+            BlendMode::Opaque => return src.with_alpha(dst.a()),
+            BlendMode::Alpha => src,
+            BlendMode::Additive => return dst.blend_add(&(src * alpha)),
+            BlendMode::Multiply => dst.blend_multiply(&src),
+            BlendMode::Screen => dst.blend_screen(&src),
+        };
+        dst.lerp(&blended, alpha).with_alpha(dst.a())
+    }
+}
+
+impl fmt::Display for BlendMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            BlendMode::Opaque => "opaque",
+            BlendMode::Alpha => "alpha",
+            BlendMode::Additive => "additive",
+            BlendMode::Multiply => "multiply",
+            BlendMode::Screen => "screen",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for BlendMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BlendMode::ALL
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("unknown blend mode '{}', expected opaque, alpha, additive, multiply or screen", s))
+    }
+}
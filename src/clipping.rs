@@ -0,0 +1,145 @@
+// clipping.rs
+
+use nalgebra_glm::{Vec3, Vec4, Mat4};
+use crate::vertex::Vertex;
+
+// Planos del frustum en clip space (convención OpenGL, -w <= x, y, z <= w).
+// Cada plano es un vector p tal que un punto está dentro si dot(p, clip_position) >= 0.
+const FRUSTUM_PLANES: [Vec4; 6] = [
+    Vec4::new(0.0, 0.0, 1.0, 1.0),  // near:   z >= -w
+    Vec4::new(0.0, 0.0, -1.0, 1.0), // far:    z <= w
+    Vec4::new(1.0, 0.0, 0.0, 1.0),  // left:   x >= -w
+    Vec4::new(-1.0, 0.0, 0.0, 1.0), // right:  x <= w
+    Vec4::new(0.0, 1.0, 0.0, 1.0),  // bottom: y >= -w
+    Vec4::new(0.0, -1.0, 0.0, 1.0), // top:    y <= w
+];
+
+fn distance(plane: &Vec4, vertex: &Vertex) -> f32 {
+    plane.dot(&vertex.clip_position)
+}
+
+// Un triángulo recortado por los seis planos tiene como mucho 3 + 6 vértices
+pub const MAX_CLIPPED_VERTICES: usize = 9;
+
+// Polígono recortado guardado en la pila, sin reservar memoria
+#[derive(Clone, Debug)]
+pub struct ClippedPolygon {
+    vertices: [Vertex; MAX_CLIPPED_VERTICES],
+    len: usize,
+}
+
+impl ClippedPolygon {
+    fn new(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Self {
+        let mut vertices = std::array::from_fn(|_| v1.clone());
+        vertices[1] = v2.clone();
+        vertices[2] = v3.clone();
+        ClippedPolygon { vertices, len: 3 }
+    }
+
+    fn push(&mut self, vertex: Vertex) {
+        self.vertices[self.len] = vertex;
+        self.len += 1;
+    }
+
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices[..self.len]
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+// Triangulación en abanico del polígono: (0, 1, 2), (0, 2, 3), ...
+impl IntoIterator for ClippedPolygon {
+    type Item = [Vertex; 3];
+    type IntoIter = FanTriangles;
+
+    fn into_iter(self) -> FanTriangles {
+        FanTriangles { polygon: self, next: 1 }
+    }
+}
+
+pub struct FanTriangles {
+    polygon: ClippedPolygon,
+    next: usize,
+}
+
+impl Iterator for FanTriangles {
+    type Item = [Vertex; 3];
+
+    fn next(&mut self) -> Option<[Vertex; 3]> {
+        if self.next + 1 >= self.polygon.len {
+            return None;
+        }
+        let vertices = &self.polygon.vertices;
+        let triangle = [vertices[0].clone(), vertices[self.next].clone(), vertices[self.next + 1].clone()];
+        self.next += 1;
+        Some(triangle)
+    }
+}
+
+// Recorta el triángulo contra los seis planos (Sutherland-Hodgman); el polígono queda
+// vacío si el triángulo está completamente fuera. No reserva memoria.
+pub fn clip_to_frustum(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> ClippedPolygon {
+    let mut polygon = ClippedPolygon::new(v1, v2, v3);
+
+    // Caso común: el triángulo está completamente dentro
+    if polygon.vertices().iter().all(|vertex| FRUSTUM_PLANES.iter().all(|plane| distance(plane, vertex) >= 0.0)) {
+        return polygon;
+    }
+
+    for plane in &FRUSTUM_PLANES {
+        if polygon.is_empty() {
+            break;
+        }
+
+        // Los vértices de `polygon` solo rellenan el arreglo; se sobrescriben al agregar
+        let mut clipped = ClippedPolygon { vertices: polygon.vertices.clone(), len: 0 };
+        let input = polygon.vertices();
+        for i in 0..input.len() {
+            let current = &input[i];
+            let next = &input[(i + 1) % input.len()];
+            let d_current = distance(plane, current);
+            let d_next = distance(plane, next);
+
+            if d_current >= 0.0 {
+                clipped.push(current.clone());
+            }
+            if (d_current >= 0.0) != (d_next >= 0.0) {
+                let t = d_current / (d_current - d_next);
+                clipped.push(current.lerp(next, t));
+            }
+        }
+        polygon = clipped;
+    }
+
+    // Menos de tres vértices no forman un triángulo
+    if polygon.len < 3 {
+        polygon.len = 0;
+    }
+    polygon
+}
+
+// Como `clip_to_frustum`, pero en un Vec
+pub fn clip_polygon(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Vertex> {
+    clip_to_frustum(v1, v2, v3).vertices().to_vec()
+}
+
+// Recorta y triangula en abanico el polígono resultante
+pub fn clip_triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<[Vertex; 3]> {
+    clip_to_frustum(v1, v2, v3).into_iter().collect()
+}
+
+// División de perspectiva y viewport: deja en transformed_position la posición en pantalla
+pub fn project_to_screen(vertex: &mut Vertex, viewport_matrix: &Mat4) {
+    let clip = vertex.clip_position;
+    let w = clip.w;
+    let ndc_position = Vec4::new(clip.x / w, clip.y / w, clip.z / w, 1.0);
+    let screen_position = viewport_matrix * ndc_position;
+    vertex.transformed_position = Vec3::new(screen_position.x, screen_position.y, screen_position.z);
+}
@@ -1,101 +1,216 @@
 // colo.rs
 
 use std::fmt;
+use std::sync::OnceLock;
+
+// Conversión exacta de un canal sRGB (0..1) a lineal
+pub fn srgb_to_linear(value: f32) -> f32 {
+	if value <= 0.04045 {
+		value / 12.92
+	} else {
+		((value + 0.055) / 1.055).powf(2.4)
+	}
+}
 
-#[derive(Debug, Clone, Copy)]
+// Conversión exacta de un canal lineal (0..1) a sRGB
+pub fn linear_to_srgb(value: f32) -> f32 {
+	if value <= 0.0031308 {
+		value * 12.92
+	} else {
+		1.055 * value.powf(1.0 / 2.4) - 0.055
+	}
+}
+
+// Tablas para las conversiones de bytes, que se hacen en cada pixel
+const ENCODE_STEPS: usize = 1 << 14;
+
+fn decode_table() -> &'static [f32; 256] {
+	static TABLE: OnceLock<[f32; 256]> = OnceLock::new();
+	TABLE.get_or_init(|| std::array::from_fn(|i| srgb_to_linear(i as f32 / 255.0)))
+}
+
+fn encode_table() -> &'static [u8] {
+	static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+	TABLE.get_or_init(|| {
+		(0..=ENCODE_STEPS)
+			.map(|i| (linear_to_srgb(i as f32 / ENCODE_STEPS as f32) * 255.0 + 0.5) as u8)
+			.collect()
+	})
+}
+
+// Canal lineal -> byte sRGB, recortando a [0, 1]
+fn encode_channel(value: f32) -> u32 {
+	let index = (value.clamp(0.0, 1.0) * ENCODE_STEPS as f32 + 0.5) as usize;
+	encode_table()[index] as u32
+}
+
+// Color RGB lineal en f32. Los bytes y valores hexadecimales se interpretan como sRGB y
+// se convierten a lineal al construir el color; la iluminación, las mezclas y la
+// interpolación trabajan en lineal y `to_hex` vuelve a codificar en sRGB. Los valores
+// pueden pasar de 1.0 (HDR); el framebuffer los lleva a [0, 1] con un tone mapper al resolver.
+// `a` es la opacidad (0 a 1, lineal): los constructores la dejan en 1 y solo la usan los
+// modos de mezcla de las superficies transparentes.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Color {
-	r: u8,
-	g: u8,
-	b: u8,
+	r: f32,
+	g: f32,
+	b: f32,
+	a: f32,
 }
 
 impl Color {
-	// Constructor to initialize the color using r, g, b values as u8
+	// Constructor con bytes sRGB (como los de un selector de color)
 	pub fn new(r: u8, g: u8, b: u8) -> Self {
-		Color { r, g, b }
+		let table = decode_table();
+		Color { r: table[r as usize], g: table[g as usize], b: table[b as usize], a: 1.0 }
+	}
+
+	// Constructor con bytes sRGB y opacidad en bytes (255 = opaco)
+	pub fn new_rgba(r: u8, g: u8, b: u8, a: u8) -> Self {
+		Color::new(r, g, b).with_alpha(a as f32 / 255.0)
+	}
+
+	// Constructor con canales sRGB en f32 (0.0 a 1.0)
+	pub fn from_srgb(r: f32, g: f32, b: f32) -> Self {
+		Color::from_linear(srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b))
+	}
+
+	// Constructor con canales lineales; igual que `from_float`
+	pub fn from_linear(r: f32, g: f32, b: f32) -> Self {
+		Color::from_float(r, g, b)
 	}
 
 	// default color
 	pub fn black() -> Self {
-		Color { r: 0, g: 0, b: 0 }
+		Color { r: 0.0, g: 0.0, b: 0.0, a: 1.0 }
 	}
 
-	// New constructor to initialize the color using r, g, b values as f32 (0.0 to 1.0)
+	// Constructor con valores lineales f32 (1.0 = blanco); solo se recortan los negativos
 	pub fn from_float(r: f32, g: f32, b: f32) -> Self {
 		Color {
-		r: (r.clamp(0.0, 1.0) * 255.0) as u8,
-		g: (g.clamp(0.0, 1.0) * 255.0) as u8,
-		b: (b.clamp(0.0, 1.0) * 255.0) as u8,
+		r: r.max(0.0),
+		g: g.max(0.0),
+		b: b.max(0.0),
+		a: 1.0,
 		}
 	}
 
-	// Function to create a color from a hex value
+	// Function to create a color from a hex value (sRGB)
 	pub fn from_hex(hex: u32) -> Self {
 		let r = ((hex >> 16) & 0xFF) as u8;
 		let g = ((hex >> 8) & 0xFF) as u8;
 		let b = (hex & 0xFF) as u8;
-		Color { r, g, b }
+		Color::new(r, g, b)
+	}
+
+	// 0xRRGGBBAA: color sRGB con la opacidad en el último byte
+	pub fn from_hex_rgba(hex: u32) -> Self {
+		Color::from_hex(hex >> 8).with_alpha((hex & 0xFF) as f32 / 255.0)
+	}
+
+	// El mismo color con otra opacidad, recortada a [0, 1]
+	pub fn with_alpha(self, a: f32) -> Self {
+		Color { a: a.clamp(0.0, 1.0), ..self }
+	}
+
+	// Function to return the color as a hex value, codificado en sRGB; los canales se recortan a [0, 1]
+	pub fn to_hex(self) -> u32 {
+		(encode_channel(self.r) << 16) | (encode_channel(self.g) << 8) | encode_channel(self.b)
 	}
 
-	// Function to return the color as a hex value
-	pub fn to_hex(&self) -> u32 {
-		((self.r as u32) << 16) | ((self.g as u32) << 8) | (self.b as u32)
+	pub fn to_linear(&self) -> [f32; 3] {
+		[self.r, self.g, self.b]
 	}
 
-	// Linear interpolation between two colors
+	// Canales sRGB en f32, sin recortar los valores HDR
+	pub fn to_srgb(&self) -> [f32; 3] {
+		[linear_to_srgb(self.r), linear_to_srgb(self.g), linear_to_srgb(self.b)]
+	}
+
+	// Canales lineales
+	pub fn r(&self) -> f32 {
+		self.r
+	}
+
+	pub fn g(&self) -> f32 {
+		self.g
+	}
+
+	pub fn b(&self) -> f32 {
+		self.b
+	}
+
+	pub fn a(&self) -> f32 {
+		self.a
+	}
+
+	pub fn is_opaque(&self) -> bool {
+		self.a >= 1.0
+	}
+
+	// Luminancia relativa (Rec. 709)
+	pub fn luminance(&self) -> f32 {
+		0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+	}
+
+	// Aplica una función a cada canal de color; la opacidad no cambia
+	pub fn map(self, f: impl Fn(f32) -> f32) -> Color {
+		Color { r: f(self.r), g: f(self.g), b: f(self.b), a: self.a }
+	}
+
+	// Linear interpolation between two colors (también de la opacidad)
 	pub fn lerp(&self, other: &Color, t: f32) -> Self {
 		let t = t.clamp(0.0, 1.0);
 		Color {
-		r: (self.r as f32 + (other.r as f32 - self.r as f32) * t).round() as u8,
-		g: (self.g as f32 + (other.g as f32 - self.g as f32) * t).round() as u8,
-		b: (self.b as f32 + (other.b as f32 - self.b as f32) * t).round() as u8,
+		r: self.r + (other.r - self.r) * t,
+		g: self.g + (other.g - self.g) * t,
+		b: self.b + (other.b - self.b) * t,
+		a: self.a + (other.a - self.a) * t,
 		}
 	}
 
+	// Solo mira el color, no la opacidad
 	pub fn is_black(&self) -> bool {
-		self.r == 0 && self.g == 0 && self.b == 0 
+		self.r == 0.0 && self.g == 0.0 && self.b == 0.0
 	}
 
-	// New blend mode methods
+	// New blend mode methods. Mezclan solo el color; la opacidad es la de `self`
 	pub fn blend_normal(&self, blend: &Color) -> Color {
 		if blend.is_black() { *self } else { *blend }
 	}
 
 	pub fn blend_multiply(&self, blend: &Color) -> Color {
-		Color::new(
-		((self.r as f32 * blend.r as f32) / 255.0) as u8,
-		((self.g as f32 * blend.g as f32) / 255.0) as u8,
-		((self.b as f32 * blend.b as f32) / 255.0) as u8
-		)
+		Color {
+		r: self.r * blend.r,
+		g: self.g * blend.g,
+		b: self.b * blend.b,
+		a: self.a,
+		}
 	}
 
 	pub fn blend_add(&self, blend: &Color) -> Color {
-		Color::new(
-		(self.r as u16 + blend.r as u16).min(255) as u8,
-		(self.g as u16 + blend.g as u16).min(255) as u8,
-		(self.b as u16 + blend.b as u16).min(255) as u8
-		)
+		*self + *blend
 	}
 
 	pub fn blend_subtract(&self, blend: &Color) -> Color {
-		let r = (self.r as i16 - blend.r as i16).max(0).min(255) as u8;
-		let g = (self.g as i16 - blend.g as i16).max(0).min(255) as u8;
-		let b = (self.b as i16 - blend.b as i16).max(0).min(255) as u8;
-
-		Color::new(r, g, b)
+		Color::from_float(self.r - blend.r, self.g - blend.g, self.b - blend.b).with_alpha(self.a)
 	}
 
+	// 1 - (1 - a)(1 - b) solo tiene sentido en [0, 1]: con HDR las entradas se recortan y
+	// un canal que ya pasaba de 1.0 se conserva, porque screen nunca oscurece
 	pub fn blend_screen(&self, blend: &Color) -> Color {
-		Color::new(
-		255 - ((255 - self.r as u16) * (255 - blend.r as u16) / 255) as u8,
-		255 - ((255 - self.g as u16) * (255 - blend.g as u16) / 255) as u8,
-		255 - ((255 - self.b as u16) * (255 - blend.b as u16) / 255) as u8
-		)
+		let screen = |a: f32, b: f32| a.max(1.0 - (1.0 - a.clamp(0.0, 1.0)) * (1.0 - b.clamp(0.0, 1.0)));
+		Color {
+		r: screen(self.r, blend.r),
+		g: screen(self.g, blend.g),
+		b: screen(self.b, blend.b),
+		a: self.a,
+		}
 	}
 
 }
 
-// Implement addition for Color
+// Implement addition for Color (sin saturar); la opacidad es la del primer operando
 use std::ops::Add;
 
 impl Add for Color {
@@ -103,9 +218,10 @@ impl Add for Color {
 
 	fn add(self, other: Color) -> Color {
 		Color {
-		r: self.r.saturating_add(other.r),
-		g: self.g.saturating_add(other.g),
-		b: self.b.saturating_add(other.b),
+		r: self.r + other.r,
+		g: self.g + other.g,
+		b: self.b + other.b,
+		a: self.a,
 		}
 	}
 }
@@ -117,17 +233,13 @@ impl Mul<f32> for Color {
 	type Output = Color;
 
 	fn mul(self, scalar: f32) -> Color {
-		Color {
-		r: (self.r as f32 * scalar).clamp(0.0, 255.0) as u8,
-		g: (self.g as f32 * scalar).clamp(0.0, 255.0) as u8,
-		b: (self.b as f32 * scalar).clamp(0.0, 255.0) as u8,
-		}
+		Color::from_float(self.r * scalar, self.g * scalar, self.b * scalar).with_alpha(self.a)
 	}
 }
 
 // Implement display formatting for Color
 impl fmt::Display for Color {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "Color(r: {}, g: {}, b: {})", self.r, self.g, self.b)
+		write!(f, "Color(r: {:.3}, g: {:.3}, b: {:.3}, a: {:.3})", self.r, self.g, self.b, self.a)
 	}
-}
\ No newline at end of file
+}
@@ -0,0 +1,39 @@
+// cubemap.rs
+//
+// Coordenadas de cube map: una dirección se proyecta sobre la cara de su componente de
+// mayor valor absoluto y da (u, v) en [0, 1]. Las caras van en el orden +X, -X, +Y, -Y,
+// +Z, -Z y la orientación de (u, v) es la de los cube maps de OpenGL.
+
+use nalgebra_glm::Vec3;
+
+// Cara y coordenadas (u, v) de la dirección `v` (no hace falta que esté normalizada)
+pub fn face_and_uv(v: &Vec3) -> (usize, f32, f32) {
+    let (x, y, z) = (v.x.abs(), v.y.abs(), v.z.abs());
+    let (face, s, t, major) = if x >= y && x >= z {
+        if v.x > 0.0 { (0, -v.z, -v.y, x) } else { (1, v.z, -v.y, x) }
+    } else if y >= z {
+        if v.y > 0.0 { (2, v.x, v.z, y) } else { (3, v.x, -v.z, y) }
+    } else if v.z > 0.0 {
+        (4, v.x, -v.y, z)
+    } else {
+        (5, -v.x, -v.y, z)
+    };
+
+    if major == 0.0 {
+        return (face, 0.5, 0.5);
+    }
+    (face, (s / major + 1.0) * 0.5, (t / major + 1.0) * 0.5)
+}
+
+// Inversa de `face_and_uv`: dirección (sin normalizar) del punto (u, v) de la cara
+pub fn face_direction(face: usize, u: f32, v: f32) -> Vec3 {
+    let (s, t) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+    match face {
+        0 => Vec3::new(1.0, -t, -s),
+        1 => Vec3::new(-1.0, -t, s),
+        2 => Vec3::new(s, 1.0, t),
+        3 => Vec3::new(s, -1.0, -t),
+        4 => Vec3::new(s, -t, 1.0),
+        _ => Vec3::new(-s, -t, -1.0),
+    }
+}
@@ -0,0 +1,43 @@
+// culling.rs
+
+use nalgebra_glm::Vec3;
+use serde::Deserialize;
+use crate::triangle::edge_function;
+
+// Qué caras se descartan antes de rasterizar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CullMode {
+    None,
+    #[default]
+    Back,
+    Front,
+}
+
+// Orden de los vértices (visto desde la cámara) que define la cara frontal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+pub enum FrontFace {
+    #[default]
+    #[serde(rename = "ccw")]
+    CounterClockwise,
+    #[serde(rename = "cw")]
+    Clockwise,
+}
+
+// `a`, `b` y `c` están en coordenadas de pantalla (Y hacia abajo), por lo que un
+// triángulo antihorario en NDC tiene edge_function positiva
+pub fn is_front_facing(a: &Vec3, b: &Vec3, c: &Vec3, front_face: FrontFace) -> bool {
+    let area = edge_function(a, b, c);
+    match front_face {
+        FrontFace::CounterClockwise => area > 0.0,
+        FrontFace::Clockwise => area < 0.0,
+    }
+}
+
+pub fn is_culled(a: &Vec3, b: &Vec3, c: &Vec3, cull_mode: CullMode, front_face: FrontFace) -> bool {
+    match cull_mode {
+        CullMode::None => false,
+        CullMode::Back => !is_front_facing(a, b, c, front_face),
+        CullMode::Front => is_front_facing(a, b, c, front_face) || edge_function(a, b, c) == 0.0,
+    }
+}
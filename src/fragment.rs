@@ -1,33 +1,61 @@
 // fragment.rs
 
 use nalgebra_glm::{Vec3, Vec2};
+use crate::antialiasing::MAX_SAMPLES;
 use crate::color::Color;
+use crate::material::NO_MATERIAL;
+use crate::varying::Varyings;
 
 pub struct Fragment {
     pub position: Vec2,
     pub color: Color,
     pub depth: f32,
+    // Normal en espacio de mundo, normalizada
     pub normal: Vec3,
+    // Luz difusa de las luces de la escena (sin su color); la calcula el renderer antes
+    // del fragment shader
     pub intensity: f32,
+    // Posición en el espacio del modelo
     pub vertex_position: Vec3,
+    pub tex_coords: Vec2,
+    pub world_position: Vec3,
+    // Varyings del vertex shader, interpolados igual que tex_coords
+    pub varyings: Varyings,
+    // Material del triángulo (el del primer vértice), ver `Uniforms::material`
+    pub material: u32,
+    // Derivadas de pantalla (cambio por pixel en x y en y) de tex_coords y vertex_position.
+    // Se calculan por bloque de 2x2 pixeles, como en una GPU, y sirven para elegir el mipmap.
+    // Solo se calculan si el sink las pide (ver `PlanetShader::needs_derivatives`).
+    pub tex_coords_dx: Vec2,
+    pub tex_coords_dy: Vec2,
+    pub vertex_position_dx: Vec3,
+    pub vertex_position_dy: Vec3,
+    // Muestras del pixel que cubre este fragmento (bit i = muestra i) y su profundidad
+    pub coverage: u32,
+    pub sample_depths: [f32; MAX_SAMPLES],
 }
 
 impl Fragment {
-    pub fn new(
-        position: Vec2,
-        color: Color,
-        depth: f32,
-        normal: Vec3,
-        intensity: f32,
-        vertex_position: Vec3,
-    ) -> Self {  
+    // Fragmento sin atributos; el rasterizador llena el resto de los campos con la sintaxis
+    // `Fragment { normal, tex_coords, ..Fragment::new(position, depth) }`
+    pub fn new(position: Vec2, depth: f32) -> Self {
         Fragment {
             position,
-            color,
+            color: Color::black(),
             depth,
-            normal,
-            intensity,
-            vertex_position,
+            normal: Vec3::zeros(),
+            intensity: 1.0,
+            vertex_position: Vec3::zeros(),
+            tex_coords: Vec2::zeros(),
+            world_position: Vec3::zeros(),
+            varyings: Varyings::new(),
+            material: NO_MATERIAL,
+            tex_coords_dx: Vec2::zeros(),
+            tex_coords_dy: Vec2::zeros(),
+            vertex_position_dx: Vec3::zeros(),
+            vertex_position_dy: Vec3::zeros(),
+            coverage: 1,
+            sample_depths: [depth; MAX_SAMPLES],
         }
     }
 }
\ No newline at end of file
@@ -1,10 +1,28 @@
 // framebuffer.rs
 
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::thread;
+
+use crate::antialiasing::AntiAliasing;
+use crate::color::Color;
+use crate::tonemap::ToneMapping;
+
+// Los fragmentos se acumulan en colores HDR (f32) por muestra; `resolve` promedia las
+// muestras de cada pixel, aplica la exposición y el tone mapper y escribe `buffer` (0xRRGGBB).
+// `zbuffer` tiene la profundidad por pixel; con anti-aliasing la profundidad se guarda por
+// muestra y `resolve` deja en `zbuffer` la más cercana.
 pub struct Framebuffer {
     pub width: usize,
     pub height: usize,
     pub buffer: Vec<u32>,
     pub zbuffer: Vec<f32>,
+    pub tone_mapping: ToneMapping,
+    pub exposure: f32,
+    antialiasing: AntiAliasing,
+    accumulation: Vec<Color>,
+    sample_zbuffer: Vec<f32>,
     background_color: u32,
     current_color: u32,
 }
@@ -16,6 +34,11 @@ impl Framebuffer {
             height,
             buffer: vec![0; width * height],
             zbuffer: vec![f32::INFINITY; width * height],
+            tone_mapping: ToneMapping::default(),
+            exposure: 1.0,
+            antialiasing: AntiAliasing::None,
+            accumulation: vec![Color::black(); width * height],
+            sample_zbuffer: Vec::new(),
             background_color: 0x000000,
             current_color: 0xFFFFFF,
         }
@@ -28,14 +51,109 @@ impl Framebuffer {
         for depth in self.zbuffer.iter_mut() {
             *depth = f32::INFINITY;
         }
+        self.accumulation.fill(Color::from_hex(self.background_color));
+        self.sample_zbuffer.fill(f32::INFINITY);
+    }
+
+    pub fn antialiasing(&self) -> AntiAliasing {
+        self.antialiasing
+    }
+
+    // Cambia el modo de anti-aliasing; las muestras empiezan con el color de fondo
+    pub fn set_antialiasing(&mut self, antialiasing: AntiAliasing) {
+        self.antialiasing = antialiasing;
+        let samples = antialiasing.sample_count();
+        self.accumulation = vec![Color::from_hex(self.background_color); self.width * self.height * samples];
+        self.sample_zbuffer = if samples > 1 {
+            vec![f32::INFINITY; self.width * self.height * samples]
+        } else {
+            Vec::new()
+        };
+    }
+
+    pub fn samples(&self) -> usize {
+        self.antialiasing.sample_count()
+    }
+
+    // Color HDR y profundidad de cada muestra, con las muestras de un pixel contiguas
+    // (índice = pixel * samples + muestra). Sin anti-aliasing la profundidad es `zbuffer`.
+    pub fn sample_targets(&mut self) -> (&mut [Color], &mut [f32]) {
+        if self.samples() > 1 {
+            (&mut self.accumulation, &mut self.sample_zbuffer)
+        } else {
+            (&mut self.accumulation, &mut self.zbuffer)
+        }
+    }
+
+    // Escribe `color_at(x, y)` en las muestras que no cubrió ningún fragmento (profundidad
+    // infinita) sin cambiar su profundidad. Las filas se reparten entre `threads` hilos.
+    pub fn fill_background(&mut self, threads: usize, color_at: impl Fn(usize, usize) -> Color + Sync) {
+        let (width, samples) = (self.width, self.samples());
+        let rows = self.height.div_ceil(threads.max(1)).max(1);
+        let (colors, depths) = self.sample_targets();
+
+        let fill = |first_row: usize, colors: &mut [Color], depths: &[f32]| {
+            let pixels = colors.chunks_exact_mut(samples).zip(depths.chunks_exact(samples));
+            for (index, (colors, depths)) in pixels.enumerate() {
+                if depths.iter().all(|depth| depth.is_finite()) {
+                    continue;
+                }
+                let color = color_at(index % width, first_row + index / width);
+                for (sample, depth) in colors.iter_mut().zip(depths) {
+                    if *depth == f32::INFINITY {
+                        *sample = color;
+                    }
+                }
+            }
+        };
+
+        if threads > 1 {
+            let chunk = rows * width * samples;
+            let fill = &fill;
+            thread::scope(|scope| {
+                for (index, (colors, depths)) in colors.chunks_mut(chunk).zip(depths.chunks(chunk)).enumerate() {
+                    scope.spawn(move || fill(index * rows, colors, depths));
+                }
+            });
+        } else {
+            fill(0, colors, depths);
+        }
+    }
+
+    // Promedia las muestras de cada pixel y las convierte a `buffer` con el tone mapper
+    pub fn resolve(&mut self) {
+        let samples = self.samples();
+        let (tone_mapping, exposure) = (self.tone_mapping, self.exposure);
+
+        if samples == 1 {
+            for (pixel, color) in self.buffer.iter_mut().zip(&self.accumulation) {
+                *pixel = tone_mapping.apply(*color, exposure).to_hex();
+            }
+        } else {
+            let scale = 1.0 / samples as f32;
+            for (pixel, colors) in self.buffer.iter_mut().zip(self.accumulation.chunks_exact(samples)) {
+                let sum = colors.iter().fold(Color::black(), |sum, color| sum + *color);
+                *pixel = tone_mapping.apply(sum * scale, exposure).to_hex();
+            }
+            for (depth, depths) in self.zbuffer.iter_mut().zip(self.sample_zbuffer.chunks_exact(samples)) {
+                *depth = depths.iter().copied().fold(f32::INFINITY, f32::min);
+            }
+        }
     }
 
+    // Escribe el color actual en todas las muestras del pixel que pasen la prueba de
+    // profundidad; se ve en `buffer` después de `resolve`
     pub fn point(&mut self, x: usize, y: usize, depth: f32) {
         if x < self.width && y < self.height {
-            let index = y * self.width + x;
-            if self.zbuffer[index] > depth {
-                self.buffer[index] = self.current_color;
-                self.zbuffer[index] = depth;
+            let samples = self.samples();
+            let base = (y * self.width + x) * samples;
+            let color = Color::from_hex(self.current_color);
+            let (colors, depths) = self.sample_targets();
+            for s in base..base + samples {
+                if depths[s] > depth {
+                    colors[s] = color;
+                    depths[s] = depth;
+                }
             }
         }
     }
@@ -47,4 +165,43 @@ impl Framebuffer {
     pub fn set_current_color(&mut self, color: u32) {
         self.current_color = color;
     }
-}
\ No newline at end of file
+
+    // Convierte el buffer 0xRRGGBB a bytes RGB consecutivos
+    pub fn to_rgb_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.buffer.len() * 3);
+        for pixel in &self.buffer {
+            bytes.push(((pixel >> 16) & 0xFF) as u8);
+            bytes.push(((pixel >> 8) & 0xFF) as u8);
+            bytes.push((pixel & 0xFF) as u8);
+        }
+        bytes
+    }
+
+    // Binary PPM (P6), no external dependencies needed
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.to_rgb_bytes())?;
+        writer.flush()
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let writer = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut png_writer = encoder.write_header().map_err(io::Error::other)?;
+        png_writer.write_image_data(&self.to_rgb_bytes()).map_err(io::Error::other)?;
+        png_writer.finish().map_err(io::Error::other)
+    }
+
+    // Elige el formato según la extensión del archivo (.ppm o .png)
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("ppm") => self.save_ppm(path),
+            _ => self.save_png(path),
+        }
+    }
+}
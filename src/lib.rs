@@ -0,0 +1,52 @@
+// lib.rs
+
+pub mod framebuffer;
+pub mod antialiasing;
+pub mod tonemap;
+pub mod blend;
+pub mod light;
+pub mod shadow;
+pub mod cubemap;
+pub mod texture;
+pub mod skybox;
+pub mod triangle;
+pub mod vertex;
+pub mod varying;
+pub mod obj;
+pub mod material;
+pub mod color;
+pub mod fragment;
+pub mod shaders;
+pub mod camera;
+pub mod matrix;
+pub mod noise;
+pub mod renderer;
+pub mod clipping;
+pub mod culling;
+pub mod tiles;
+pub mod planet_shader;
+pub mod scene;
+pub mod orbit;
+pub mod scene_file;
+pub mod watcher;
+
+pub use framebuffer::Framebuffer;
+pub use vertex::Vertex;
+pub use varying::Varyings;
+pub use obj::Obj;
+pub use material::Material;
+pub use color::Color;
+pub use fragment::Fragment;
+pub use camera::Camera;
+pub use renderer::{RasterState, Renderer, Uniforms};
+pub use planet_shader::{PlanetShader, ShaderRegistry, ShaderParameter, ParamValue, ParameterError};
+pub use scene::{Body, HierarchyError, Scene, SceneLight};
+pub use orbit::Orbit;
+pub use culling::{CullMode, FrontFace};
+pub use antialiasing::{AntiAliasing, Samples};
+pub use tonemap::ToneMapping;
+pub use blend::BlendMode;
+pub use light::{Light, LightKind};
+pub use shadow::ShadowSettings;
+pub use skybox::{ImageSky, ProceduralSky, SkyImage, Skybox};
+pub use texture::{CubeMap, Filter, Sampler, Texture, WrapMode};
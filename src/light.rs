@@ -0,0 +1,114 @@
+// light.rs
+//
+// Luces de la escena y modelos de iluminación. Todo se calcula en espacio de mundo: las
+// posiciones y normales de los fragmentos, las posiciones de las luces y la cámara.
+
+use nalgebra_glm::{dot, Vec3};
+
+use crate::color::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    // Emite en todas direcciones desde un punto, sin atenuación con la distancia
+    Point { position: Vec3 },
+    // Rayos paralelos; `direction` es hacia donde viaja la luz
+    Directional { direction: Vec3 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub kind: LightKind,
+    pub color: Color,
+    pub intensity: f32,
+}
+
+impl Light {
+    pub fn point(position: Vec3, color: Color, intensity: f32) -> Self {
+        Light { kind: LightKind::Point { position }, color, intensity }
+    }
+
+    pub fn directional(direction: Vec3, color: Color, intensity: f32) -> Self {
+        Light { kind: LightKind::Directional { direction: direction.normalize() }, color, intensity }
+    }
+
+    // La luz por defecto cuando la escena no define ninguna: blanca y viajando hacia -Z,
+    // igual que la iluminación fija de antes
+    pub fn headlight() -> Self {
+        Light::directional(Vec3::new(0.0, 0.0, -1.0), Color::from_float(1.0, 1.0, 1.0), 1.0)
+    }
+
+    // Dirección normalizada desde `point` hacia la luz
+    pub fn direction_from(&self, point: &Vec3) -> Vec3 {
+        match self.kind {
+            LightKind::Point { position } => {
+                let to_light = position - point;
+                if to_light.magnitude_squared() > 0.0 { to_light.normalize() } else { Vec3::zeros() }
+            }
+            LightKind::Directional { direction } => -direction,
+        }
+    }
+
+    // Color por intensidad
+    pub fn radiance(&self) -> Color {
+        self.color * self.intensity
+    }
+}
+
+// Término difuso de Lambert; `normal` y `to_light` deben estar normalizados
+pub fn lambert(normal: &Vec3, to_light: &Vec3) -> f32 {
+    dot(normal, to_light).max(0.0)
+}
+
+// Término especular de Blinn-Phong con el vector medio entre la luz y el ojo. Es 0 en
+// las caras que no miran a la luz para que no aparezcan brillos en el lado nocturno.
+pub fn blinn_phong(normal: &Vec3, to_light: &Vec3, to_eye: &Vec3, shininess: f32) -> f32 {
+    if dot(normal, to_light) <= 0.0 {
+        return 0.0;
+    }
+    let half = to_light + to_eye;
+    if half.magnitude_squared() == 0.0 {
+        return 0.0;
+    }
+    dot(normal, &half.normalize()).max(0.0).powf(shininess)
+}
+
+// Luces que llegan al punto: la luz, la dirección hacia ella, su término de Lambert y la
+// fracción sin sombra. `visibility` recibe el índice de la luz (1 = sin sombra) y solo se
+// consulta en el lado iluminado; sin sombras basta con `|_| 1.0`.
+fn visible_lights<'a, V>(lights: &'a [Light], position: &'a Vec3, normal: &'a Vec3, visibility: V) -> impl Iterator<Item = (&'a Light, Vec3, f32, f32)> + 'a
+where
+    V: Fn(usize) -> f32 + 'a,
+{
+    lights.iter().enumerate().filter_map(move |(index, light)| {
+        let to_light = light.direction_from(position);
+        let diffuse = lambert(normal, &to_light);
+        if diffuse <= 0.0 {
+            return None;
+        }
+        let visible = visibility(index);
+        (visible > 0.0).then_some((light, to_light, diffuse, visible))
+    })
+}
+
+// Suma de intensidad · Lambert de todas las luces, sin su color (es `Fragment::intensity`)
+pub fn diffuse_intensity(lights: &[Light], position: &Vec3, normal: &Vec3, visibility: impl Fn(usize) -> f32) -> f32 {
+    visible_lights(lights, position, normal, visibility)
+        .map(|(light, _, diffuse, visible)| light.intensity * diffuse * visible)
+        .sum()
+}
+
+// Luz difusa que llega al punto, con el color de cada luz
+pub fn diffuse(lights: &[Light], position: &Vec3, normal: &Vec3, visibility: impl Fn(usize) -> f32) -> Color {
+    visible_lights(lights, position, normal, visibility).fold(Color::black(), |sum, (light, _, diffuse, visible)| {
+        sum + light.radiance() * (diffuse * visible)
+    })
+}
+
+// Luz especular (Blinn-Phong) que llega al punto visto desde `eye`
+pub fn specular(lights: &[Light], position: &Vec3, normal: &Vec3, eye: &Vec3, shininess: f32, visibility: impl Fn(usize) -> f32) -> Color {
+    let to_eye = eye - position;
+    let to_eye = if to_eye.magnitude_squared() > 0.0 { to_eye.normalize() } else { Vec3::zeros() };
+    visible_lights(lights, position, normal, visibility).fold(Color::black(), |sum, (light, to_light, _, visible)| {
+        sum + light.radiance() * (blinn_phong(normal, &to_light, &to_eye, shininess) * visible)
+    })
+}
@@ -1,225 +1,279 @@
 // main.rs
 
-use nalgebra_glm::{Vec3, Mat4, look_at, perspective};
+use nalgebra_glm::Vec3;
 use minifb::{Key, Window, WindowOptions};
-use core::num;
-use std::time::Duration;
+use std::env;
 use std::f32::consts::PI;
-use std::rc::Rc;
-
-mod framebuffer;
-mod triangle;
-mod vertex;
-mod obj;
-mod color;
-mod fragment;
-mod shaders;
-mod camera;
-
-use framebuffer::Framebuffer;
-use vertex::Vertex;
-use obj::Obj;
-use triangle::triangle;
-use shaders::{vertex_shader, fragment_shader};
-use camera::Camera;
-use fastnoise_lite::{FastNoiseLite, NoiseType, FractalType};
-
-pub struct Uniforms {
-    model_matrix: Mat4,
-    view_matrix: Mat4,
-    projection_matrix: Mat4,
-    viewport_matrix: Mat4,
-    time: u32,
-    noise: Rc<FastNoiseLite>,
-}
-
-fn create_noise_for_planet(index: usize) -> FastNoiseLite {
-    match index {
-        0 => create_lava_noise(),
-        1 => create_gas_giant_noise(),
-        2 => create_generic_noise(),
-        3 => create_ground_noise(),
-        4 => create_cloud_noise(),
-        5 => create_icy_noise(),
-        6 => create_generic_noise(),
-        7 => create_generic_noise(),
-        8 => create_generic_noise(),
-        9 => create_generic_noise(),
-        _ => create_generic_noise(),
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::Arc;
+use fastnoise_lite::FastNoiseLite;
+
+use graficas_lab4::{AntiAliasing, Body, Camera, Obj, Renderer, Scene, ShaderRegistry, ToneMapping, Vertex};
+use graficas_lab4::scene_file::{load_parameters, load_scene, LoadedScene, SceneError};
+use graficas_lab4::watcher::FileWatcher;
+use graficas_lab4::noise::create_noise_for_planet;
+use graficas_lab4::triangle::Interpolation;
+
+// Pasa al siguiente shader del registro
+fn switch_shader(registry: &ShaderRegistry, body: &mut Body) {
+    if let Some(next) = registry.next_name(body.shader.name()).and_then(|name| registry.create(name)) {
+        body.set_shader(next);
     }
 }
 
-fn create_generic_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(1337);
-    noise.set_noise_type(Some(NoiseType::Perlin));  // Usar Perlin por defecto
-    noise.set_frequency(Some(0.05));               // Frecuencia básica
-    noise
-}
-
-fn create_icy_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(7890);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2)); // Simplex para suaves transiciones
-    noise.set_frequency(Some(0.08));                    // Frecuencia más alta
-    noise.set_fractal_type(Some(FractalType::FBm));     // Más octavas para textura
-    noise
+// Cada shader usa el ruido que le corresponde según su posición en el registro
+fn noise_for_shader(registry: &ShaderRegistry, name: &str) -> Arc<FastNoiseLite> {
+    let index = registry.index_of(name).unwrap_or(usize::MAX);
+    Arc::new(create_noise_for_planet(index))
 }
 
-fn create_gas_giant_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(4242);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2)); // Efecto de bandas suaves
-    noise.set_frequency(Some(0.02));                    // Características grandes
-    noise
+fn create_body(registry: &ShaderRegistry, mesh: &Arc<Vec<Vertex>>, name: &str, shader: &str) -> Body {
+    let planet_shader = registry.create(shader).expect("shader not registered");
+    Body::new(name, Arc::clone(mesh), planet_shader, noise_for_shader(registry, shader))
 }
 
-fn create_cloud_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(1337);
-    noise.set_noise_type(Some(NoiseType::OpenSimplex2));
-    noise
+// Un solo planeta en el origen con el shader indicado
+fn create_showcase_scene(registry: &ShaderRegistry, mesh: &Arc<Vec<Vertex>>, shader: &str) -> Scene {
+    let mut scene = Scene::new();
+    scene.add(create_body(registry, mesh, shader, shader)).expect("bodies without orbit always fit");
+    scene
 }
 
+// Escena del visor: un solo planeta con --shader o el archivo de escena, más los
+// parámetros de --params si se indicaron
+fn load_viewer_scene(options: &Options, registry: &ShaderRegistry) -> Result<LoadedScene, SceneError> {
+    let mut loaded = match &options.shader {
+        // Solo el modo --shader usa la esfera; las escenas cargan sus propias mallas
+        Some(shader) => {
+            let path = &options.mesh;
+            let obj = Obj::load(&path.to_string_lossy())
+                .map_err(|source| SceneError::Mesh { path: path.clone(), source })?;
+            let mesh = Arc::new(obj.get_vertex_array());
+            LoadedScene {
+                scene: create_showcase_scene(registry, &mesh, shader),
+                camera: Some(Camera::new(
+                    Vec3::new(0.0, 0.0, 5.0),
+                    Vec3::new(0.0, 0.0, 0.0),
+                    Vec3::new(0.0, 1.0, 0.0)
+                )),
+                warnings: obj.warnings().to_vec(),
+            }
+        }
+        None => load_scene(&options.scene, registry)?,
+    };
+    for warning in &loaded.warnings {
+        eprintln!("warning: {}", warning);
+    }
 
-fn create_ground_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(1337);
-    
-    // Use FBm fractal type to layer multiple octaves of noise
-    noise.set_noise_type(Some(NoiseType::Cellular)); // Cellular noise for cracks
-    noise.set_fractal_type(Some(FractalType::FBm));  // Fractal Brownian Motion
-    noise.set_fractal_octaves(Some(5));              // More octaves = more detail
-    noise.set_fractal_lacunarity(Some(2.0));         // Lacunarity controls frequency scaling
-    noise.set_fractal_gain(Some(0.5));               // Gain controls amplitude scaling
-    noise.set_frequency(Some(0.05));                 // Lower frequency for larger features
+    if let Some(params) = &options.params {
+        load_parameters(params)?.apply(&mut loaded.scene)?;
+    }
+    if options.no_sky {
+        loaded.scene.sky = None;
+    }
 
-    noise
+    Ok(loaded)
 }
 
-fn create_lava_noise() -> FastNoiseLite {
-    let mut noise = FastNoiseLite::with_seed(42);
-    
-    // Use FBm for multi-layered noise, giving a "turbulent" feel
-    noise.set_noise_type(Some(NoiseType::Perlin));  // Perlin noise for smooth, natural texture
-    noise.set_fractal_type(Some(FractalType::FBm)); // FBm for layered detail
-    noise.set_fractal_octaves(Some(6));             // High octaves for rich detail
-    noise.set_fractal_lacunarity(Some(2.0));        // Higher lacunarity = more contrast between layers
-    noise.set_fractal_gain(Some(0.5));              // Higher gain = more influence of smaller details
-    noise.set_frequency(Some(0.002));                // Low frequency = large features
-    
-    noise
+// Dibuja un frame completo: limpia el framebuffer y renderiza todos los cuerpos de la escena
+fn render_frame(renderer: &mut Renderer, scene: &Scene, camera: &Camera) {
+    renderer.clear();
+    renderer.set_camera(camera);
+    renderer.draw_scene(scene);
 }
 
-fn create_model_matrix(translation: Vec3, scale: f32, rotation: Vec3) -> Mat4 {
-    let (sin_x, cos_x) = rotation.x.sin_cos();
-    let (sin_y, cos_y) = rotation.y.sin_cos();
-    let (sin_z, cos_z) = rotation.z.sin_cos();
-
-    let rotation_matrix_x = Mat4::new(
-        1.0,  0.0,    0.0,   0.0,
-        0.0,  cos_x, -sin_x, 0.0,
-        0.0,  sin_x,  cos_x, 0.0,
-        0.0,  0.0,    0.0,   1.0,
-    );
-
-    let rotation_matrix_y = Mat4::new(
-        cos_y,  0.0,  sin_y, 0.0,
-        0.0,    1.0,  0.0,   0.0,
-        -sin_y, 0.0,  cos_y, 0.0,
-        0.0,    0.0,  0.0,   1.0,
-    );
-
-    let rotation_matrix_z = Mat4::new(
-        cos_z, -sin_z, 0.0, 0.0,
-        sin_z,  cos_z, 0.0, 0.0,
-        0.0,    0.0,  1.0, 0.0,
-        0.0,    0.0,  0.0, 1.0,
-    );
-
-    let rotation_matrix = rotation_matrix_z * rotation_matrix_y * rotation_matrix_x;
-
-    let transform_matrix = Mat4::new(
-        scale, 0.0,   0.0,   translation.x,
-        0.0,   scale, 0.0,   translation.y,
-        0.0,   0.0,   scale, translation.z,
-        0.0,   0.0,   0.0,   1.0,
-    );
-
-    transform_matrix * rotation_matrix
+fn window_title(scene: &Scene, focused: usize) -> String {
+    match scene.bodies.get(focused) {
+        Some(body) => format!("Graficas por Computadora Shaders - {} ({})", body.name, body.shader.name()),
+        None => "Graficas por Computadora Shaders".to_string(),
+    }
 }
 
+// Opciones de línea de comandos
+struct Options {
+    headless: bool,
+    list_shaders: bool,
+    affine: bool,
+    no_shadows: bool,
+    no_sky: bool,
+    threads: Option<usize>,
+    antialiasing: AntiAliasing,
+    tone_mapping: ToneMapping,
+    exposure: f32,
+    frames: u32,
+    time: u32,
+    shader: Option<String>,
+    mesh: PathBuf,
+    scene: PathBuf,
+    params: Option<PathBuf>,
+    width: usize,
+    height: usize,
+    output: PathBuf,
+}
 
-fn create_view_matrix(eye: Vec3, center: Vec3, up: Vec3) -> Mat4 {
-    look_at(&eye, &center, &up)
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            headless: false,
+            list_shaders: false,
+            affine: false,
+            no_shadows: false,
+            no_sky: false,
+            threads: None,
+            antialiasing: AntiAliasing::None,
+            tone_mapping: ToneMapping::Clamp,
+            exposure: 1.0,
+            frames: 1,
+            time: 0,
+            shader: None,
+            mesh: PathBuf::from("assets/model/sphere.obj"),
+            scene: PathBuf::from("assets/scenes/solar_system.toml"),
+            params: None,
+            width: 800,
+            height: 600,
+            output: PathBuf::from("frame.png"),
+        }
+    }
 }
 
-fn create_perspective_matrix(window_width: f32, window_height: f32) -> Mat4 {
-    let fov = 45.0 * PI / 180.0;
-    let aspect_ratio = window_width / window_height;
-    let near = 0.1;
-    let far = 1000.0;
+fn parse_options() -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {}", name));
+        match arg.as_str() {
+            "--headless" => options.headless = true,
+            "--frames" => options.frames = parse_value(&arg, &value(&arg)?)?,
+            "--time" => options.time = parse_value(&arg, &value(&arg)?)?,
+            "--list-shaders" => options.list_shaders = true,
+            "--affine" => options.affine = true,
+            "--no-shadows" => options.no_shadows = true,
+            "--no-sky" => options.no_sky = true,
+            "--threads" => options.threads = Some(parse_value(&arg, &value(&arg)?)?),
+            "--aa" => options.antialiasing = value(&arg)?.parse()?,
+            "--tonemap" => options.tone_mapping = value(&arg)?.parse()?,
+            "--exposure" => options.exposure = parse_value(&arg, &value(&arg)?)?,
+            "--shader" => options.shader = Some(value(&arg)?),
+            "--mesh" => options.mesh = PathBuf::from(value(&arg)?),
+            "--scene" => options.scene = PathBuf::from(value(&arg)?),
+            "--params" => options.params = Some(PathBuf::from(value(&arg)?)),
+            "--width" => options.width = parse_value(&arg, &value(&arg)?)?,
+            "--height" => options.height = parse_value(&arg, &value(&arg)?)?,
+            "--output" => options.output = PathBuf::from(value(&arg)?),
+            _ => return Err(format!("unknown argument: {}", arg)),
+        }
+    }
+
+    if options.frames == 0 {
+        return Err("--frames must be at least 1".to_string());
+    }
+    if options.width == 0 || options.height == 0 {
+        return Err("--width and --height must be greater than 0".to_string());
+    }
+    if options.threads == Some(0) {
+        return Err("--threads must be at least 1".to_string());
+    }
+    if options.exposure <= 0.0 || options.exposure.is_nan() {
+        return Err("--exposure must be greater than 0".to_string());
+    }
 
-    perspective(fov, aspect_ratio, near, far)
+    Ok(options)
 }
 
-fn create_viewport_matrix(width: f32, height: f32) -> Mat4 {
-    Mat4::new(
-        width / 2.0, 0.0, 0.0, width / 2.0,
-        0.0, -height / 2.0, 0.0, height / 2.0,
-        0.0, 0.0, 1.0, 0.0,
-        0.0, 0.0, 0.0, 1.0
-    )
+fn parse_value<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, String> {
+    value.parse().map_err(|_| format!("invalid value for {}: {}", name, value))
 }
 
-fn render(framebuffer: &mut Framebuffer, uniforms: &Uniforms, vertex_array: &[Vertex], current_shader: u32) {
-    let mut transformed_vertices = Vec::with_capacity(vertex_array.len());
-    for vertex in vertex_array {
-        let transformed = vertex_shader(vertex, uniforms);
-        transformed_vertices.push(transformed);
+// Ruta de salida de cada frame: si hay más de uno se numeran (frame_0000.png, ...)
+fn frame_output_path(output: &Path, frame: u32, frames: u32) -> PathBuf {
+    if frames == 1 {
+        return output.to_path_buf();
     }
 
-    let mut triangles = Vec::new();
-    for i in (0..transformed_vertices.len()).step_by(3) {
-        if i + 2 < transformed_vertices.len() {
-            triangles.push([
-                transformed_vertices[i].clone(),
-                transformed_vertices[i + 1].clone(),
-                transformed_vertices[i + 2].clone(),
-            ]);
+    let stem = output.file_stem().and_then(|s| s.to_str()).unwrap_or("frame");
+    let extension = output.extension().and_then(|s| s.to_str()).unwrap_or("png");
+    output.with_file_name(format!("{}_{:04}.{}", stem, frame, extension))
+}
+
+fn main() {
+    let options = match parse_options() {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!("usage: Graficas_lab4 [--headless] [--frames N] [--time T] [--shader NAME] [--mesh FILE.obj] [--scene FILE.toml] [--params FILE.toml] [--list-shaders] [--affine] [--no-shadows] [--no-sky] [--threads N] [--aa none|msaa2|msaa4|msaa8|ssaa2|ssaa4|ssaa8] [--tonemap clamp|reinhard|aces|exposure] [--exposure E] [--width W] [--height H] [--output FILE.png|FILE.ppm]");
+            process::exit(2);
         }
-    }
+    };
 
-    let mut fragments = Vec::new();
-    for tri in &triangles {
-        fragments.extend(triangle(&tri[0], &tri[1], &tri[2]));
+    let mut renderer = Renderer::new(options.width, options.height);
+    renderer.framebuffer.set_background_color(0x333355);
+    if options.affine {
+        renderer.raster.interpolation = Interpolation::Affine;
+    }
+    if let Some(threads) = options.threads {
+        renderer.raster.threads = threads;
+    }
+    renderer.uniforms.shadow_settings.enabled = !options.no_shadows;
+    renderer.set_antialiasing(options.antialiasing);
+    renderer.framebuffer.tone_mapping = options.tone_mapping;
+    renderer.framebuffer.exposure = options.exposure;
+
+    let registry = ShaderRegistry::with_builtin_shaders();
+    if options.list_shaders {
+        for name in registry.names() {
+            println!("{}", name);
+        }
+        return;
     }
 
-    for fragment in fragments {
-        let x = fragment.position.x as usize;
-        let y = fragment.position.y as usize;
+	let mut time = options.time;
 
-        if x < framebuffer.width && y < framebuffer.height {
-            let shaded_color = fragment_shader(&fragment, &uniforms, current_shader);
-            let color = shaded_color.to_hex();
-            framebuffer.set_current_color(color);
-            framebuffer.point(x, y, fragment.depth);
+    if let Some(shader) = &options.shader {
+        if registry.create(shader).is_none() {
+            eprintln!("error: unknown shader '{}', available: {}", shader, registry.names().join(", "));
+            process::exit(2);
         }
     }
-}
-
-fn switch_shader(current_shader: &mut u32, total_shaders: u32) {
-    *current_shader = (*current_shader + 1) % total_shaders;
-}
 
+    let (mut scene, camera) = match load_viewer_scene(&options, &registry) {
+        Ok(loaded) => (loaded.scene, loaded.camera),
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(1);
+        }
+    };
+    let mut camera = camera.unwrap_or_else(|| Camera::new(
+        Vec3::new(0.0, 8.0, 18.0),
+        Vec3::new(0.0, 0.0, 0.0),
+        Vec3::new(0.0, 1.0, 0.0)
+    ));
+
+    if options.headless {
+        // Sin ventana: se renderizan N frames a partir del tiempo indicado y se guardan a disco
+        for frame in 0..options.frames {
+            renderer.set_time(time);
+            scene.update(time);
+            render_frame(&mut renderer, &scene, &camera);
+
+            let path = frame_output_path(&options.output, frame, options.frames);
+            if let Err(error) = renderer.framebuffer.save(&path) {
+                eprintln!("error: failed to write {}: {}", path.display(), error);
+                process::exit(1);
+            }
+            time += 1;
+        }
+        return;
+    }
 
-fn main() {
-    let window_width = 800;
-    let window_height = 600;
-    let framebuffer_width = 800;
-    let framebuffer_height = 600;
-    let frame_delay = Duration::from_millis(16);
+    // Cuerpo seleccionado: Tab cambia de cuerpo y C cambia su shader
+    let mut focused = 0;
 
-    let mut framebuffer = Framebuffer::new(framebuffer_width, framebuffer_height);
     let mut window = Window::new(
-        "Graficas por Computadora Shaders",
-        window_width,
-        window_height,
+        &window_title(&scene, focused),
+        renderer.width(),
+        renderer.height(),
         WindowOptions::default(),
     )
     .unwrap();
@@ -227,84 +281,96 @@ fn main() {
     window.set_position(500, 500);
     window.update();
 
-    framebuffer.set_background_color(0x333355);
-
-	// model position
-	let translation = Vec3::new(0.0, 0.0, 0.0);
-	let rotation = Vec3::new(0.0, 0.0, 0.0);
-	let scale = 1.0f32;
-    let mut current_shader = 0; // Shader inicial
-	// camera parameters
-	let mut camera = Camera::new(
-        Vec3::new(0.0, 0.0, 5.0),
-        Vec3::new(0.0, 0.0, 0.0),
-        Vec3::new(0.0, 1.0, 0.0)
-    );
-
-	let obj = Obj::load("assets/model/sphere.obj").expect("Failed to load obj");
-	let vertex_arrays = obj.get_vertex_array(); 
-	let mut time = 0;
-
-    let mut noises: Vec<Rc<FastNoiseLite>> = Vec::new();
-    for i in 0..7 {
-        noises.push(Rc::new(create_noise_for_planet(i)));
+    // Recarga automática: si cambia la escena se vuelve a leer completa; si solo cambia el
+    // archivo de parámetros, se aplica sobre la escena actual
+    let mut watcher = FileWatcher::new();
+    if options.shader.is_none() {
+        watcher.watch(&options.scene);
     }
-    
-    let mut planets = Vec::new();
-    let generic_noise = Rc::new(create_generic_noise());
-    for (i, vertex_array) in vertex_arrays.chunks(3).enumerate() {
-        let noise = noises.get(i).unwrap_or(&generic_noise); // Usa ruido genérico si no hay otro disponible
-        planets.push((vertex_array, Rc::clone(noise))); // Clonamos la referencia
+    if let Some(params) = &options.params {
+        watcher.watch(params);
     }
 
-    
-    let projection_matrix = create_perspective_matrix(window_width as f32, window_height as f32);
-    let viewport_matrix = create_viewport_matrix(framebuffer_width as f32, framebuffer_height as f32);
-    let mut uniforms = Uniforms { 
-        model_matrix: Mat4::identity(), 
-        view_matrix: Mat4::identity(), 
-        projection_matrix, 
-        viewport_matrix, 
-        time: 0, 
-        noise: create_generic_noise().into(),
-    };
-
     while window.is_open() {
         if window.is_key_down(Key::Escape) {
             break;
         }
 
-        let number_of_planets = 9;
+        let changed = watcher.changed();
+        if changed.contains(&options.scene) {
+            // Si el archivo tiene errores se conserva la escena anterior
+            match load_viewer_scene(&options, &registry) {
+                Ok(loaded) => {
+                    scene = loaded.scene;
+                    focused = focused.min(scene.bodies.len().saturating_sub(1));
+                    window.set_title(&window_title(&scene, focused));
+                    println!("reloaded scene");
+                }
+                Err(error) => eprintln!("error: {}", error),
+            }
+        } else if !changed.is_empty() {
+            // Solo cambiaron los parámetros: se aplican sobre la escena actual, sin volver a
+            // cargar mallas ni texturas y conservando los shaders elegidos con C
+            if let Some(params) = &options.params {
+                match load_parameters(params).and_then(|parameters| parameters.apply(&mut scene)) {
+                    Ok(()) => println!("reloaded parameters"),
+                    Err(error) => eprintln!("error: {}", error),
+                }
+            }
+        }
 
-        // Cambiar el shader al presionar "S"
-        if window.is_key_pressed(Key::C, minifb::KeyRepeat::No) {
-            current_shader = (current_shader + 1) % number_of_planets; 
+        if window.is_key_pressed(Key::Tab, minifb::KeyRepeat::No) && !scene.bodies.is_empty() {
+            focused = (focused + 1) % scene.bodies.len();
+            window.set_title(&window_title(&scene, focused));
         }
 
-        time += 1;
+        // Cambiar el shader al presionar "C"
+        if window.is_key_pressed(Key::C, minifb::KeyRepeat::No) && !scene.bodies.is_empty() {
+            let body = &mut scene.bodies[focused];
+            switch_shader(&registry, body);
+            body.noise = noise_for_shader(&registry, body.shader.name());
+            window.set_title(&window_title(&scene, focused));
+        }
 
-        handle_input(&window, &mut camera);
+        // "I" alterna entre interpolación con corrección de perspectiva y afín
+        if window.is_key_pressed(Key::I, minifb::KeyRepeat::No) {
+            renderer.raster.interpolation = renderer.raster.interpolation.toggle();
+            println!("interpolation: {:?}", renderer.raster.interpolation);
+        }
 
-        framebuffer.clear();
+        // "M" pasa al siguiente modo de anti-aliasing
+        if window.is_key_pressed(Key::M, minifb::KeyRepeat::No) {
+            renderer.set_antialiasing(renderer.framebuffer.antialiasing().next());
+            println!("anti-aliasing: {}", renderer.framebuffer.antialiasing());
+        }
 
-        uniforms.model_matrix = create_model_matrix(translation, scale, rotation);
-        uniforms.view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
-        uniforms.time = time;
-        framebuffer.set_current_color(0xFFDDDD);
+        // "T" pasa al siguiente tone mapper
+        if window.is_key_pressed(Key::T, minifb::KeyRepeat::No) {
+            renderer.framebuffer.tone_mapping = renderer.framebuffer.tone_mapping.next();
+            println!("tone mapping: {}", renderer.framebuffer.tone_mapping);
+        }
 
-        // Renderiza cada planeta con su ruido asignado
-        for (planet_index, (vertex_array, noise)) in planets.iter().enumerate() {
-            uniforms.noise = Rc::clone(noise); // Clona la referencia, no el valor
-            render(&mut framebuffer, &uniforms, vertex_array, current_shader);
+        // "H" activa o desactiva las sombras
+        if window.is_key_pressed(Key::H, minifb::KeyRepeat::No) {
+            let shadows = &mut renderer.uniforms.shadow_settings.enabled;
+            *shadows = !*shadows;
+            println!("shadows: {}", if *shadows { "on" } else { "off" });
         }
 
+        time += 1;
+
+        handle_input(&window, &mut camera);
+
+        renderer.set_time(time);
+        scene.update(time);
+        render_frame(&mut renderer, &scene, &camera);
+
         window
-            .update_with_buffer(&framebuffer.buffer, framebuffer_width, framebuffer_height)
+            .update_with_buffer(&renderer.framebuffer.buffer, renderer.width(), renderer.height())
             .unwrap();
     }
 }
 
-
 fn handle_input(window: &Window, camera: &mut Camera) {
     let movement_speed = 0.90;
     let rotation_speed = PI/60.0;
@@ -0,0 +1,81 @@
+// material.rs
+//
+// Materiales de las bibliotecas .mtl que acompañan a los OBJ. Cada malla del OBJ apunta a uno
+// y sus vértices guardan el índice (`Vertex::material`), que llega sin interpolar al fragmento.
+// Los colores Kd, Ks y Ke se toman como lineales; el mapa difuso es sRGB como toda textura.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use crate::color::Color;
+use crate::texture::Texture;
+
+// Índice de los vértices que no tienen material; `Uniforms::material` devuelve None
+pub const NO_MATERIAL: u32 = u32::MAX;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub name: String,
+    // Kd
+    pub diffuse: Color,
+    // Ks y Ns (exponente de Blinn-Phong)
+    pub specular: Color,
+    pub shininess: f32,
+    // Ke: luz propia, se suma sin importar las luces de la escena
+    pub emissive: Color,
+    // d (1 = opaco)
+    pub opacity: f32,
+    // map_Kd, multiplica a `diffuse`
+    pub diffuse_map: Option<Arc<Texture>>,
+}
+
+// Gris mate, como el material por defecto de Blender
+impl Default for Material {
+    fn default() -> Self {
+        Material {
+            name: "default".to_string(),
+            diffuse: Color::from_float(0.8, 0.8, 0.8),
+            specular: Color::black(),
+            shininess: 32.0,
+            emissive: Color::black(),
+            opacity: 1.0,
+            diffuse_map: None,
+        }
+    }
+}
+
+impl Material {
+    // Los campos que faltan en el .mtl quedan como en `Material::default`. Las rutas de las
+    // texturas son relativas a `directory`; si una no se puede cargar se agrega un aviso a
+    // `warnings` y el material queda sin ese mapa.
+    pub fn from_mtl(material: &tobj::Material, directory: &Path, warnings: &mut Vec<String>) -> Self {
+        let defaults = Material::default();
+        let color = |rgb: Option<[f32; 3]>, default: Color| rgb.map_or(default, |[r, g, b]| Color::from_float(r, g, b));
+
+        let emissive = material.unknown_param.get("Ke").and_then(|value| parse_rgb(value));
+        let diffuse_map = material.diffuse_texture.as_ref().and_then(|file| {
+            let path = directory.join(file);
+            Texture::load(&path)
+                .map(Arc::new)
+                .map_err(|error| warnings.push(format!("material '{}': failed to load map_Kd: {}", material.name, error)))
+                .ok()
+        });
+
+        Material {
+            name: material.name.clone(),
+            diffuse: color(material.diffuse, defaults.diffuse),
+            specular: color(material.specular, defaults.specular),
+            shininess: material.shininess.unwrap_or(defaults.shininess),
+            emissive: color(emissive, defaults.emissive),
+            opacity: material.dissolve.unwrap_or(defaults.opacity).clamp(0.0, 1.0),
+            diffuse_map,
+        }
+    }
+}
+
+// Tres números separados por espacios, como "0.5 0.5 0.5"
+fn parse_rgb(value: &str) -> Option<[f32; 3]> {
+    let mut numbers = value.split_whitespace().map(|number| number.parse::<f32>().ok());
+    let rgb = [numbers.next()??, numbers.next()??, numbers.next()??];
+    numbers.next().is_none().then_some(rgb)
+}
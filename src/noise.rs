@@ -0,0 +1,223 @@
+// noise.rs
+
+use fastnoise_lite::{FastNoiseLite, NoiseType, FractalType};
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoiseKind {
+    OpenSimplex2,
+    OpenSimplex2s,
+    Cellular,
+    Perlin,
+    ValueCubic,
+    Value,
+}
+
+impl From<NoiseKind> for NoiseType {
+    fn from(kind: NoiseKind) -> Self {
+        match kind {
+            NoiseKind::OpenSimplex2 => NoiseType::OpenSimplex2,
+            NoiseKind::OpenSimplex2s => NoiseType::OpenSimplex2S,
+            NoiseKind::Cellular => NoiseType::Cellular,
+            NoiseKind::Perlin => NoiseType::Perlin,
+            NoiseKind::ValueCubic => NoiseType::ValueCubic,
+            NoiseKind::Value => NoiseType::Value,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FractalKind {
+    None,
+    Fbm,
+    Ridged,
+    PingPong,
+}
+
+impl From<FractalKind> for FractalType {
+    fn from(kind: FractalKind) -> Self {
+        match kind {
+            FractalKind::None => FractalType::None,
+            FractalKind::Fbm => FractalType::FBm,
+            FractalKind::Ridged => FractalType::Ridged,
+            FractalKind::PingPong => FractalType::PingPong,
+        }
+    }
+}
+
+// Configuración de un generador de ruido. Los campos en None conservan el valor
+// por defecto de FastNoiseLite.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NoiseSettings {
+    pub seed: i32,
+    #[serde(rename = "type")]
+    pub noise_type: Option<NoiseKind>,
+    pub frequency: Option<f32>,
+    pub fractal_type: Option<FractalKind>,
+    pub octaves: Option<i32>,
+    pub lacunarity: Option<f32>,
+    pub gain: Option<f32>,
+}
+
+impl NoiseSettings {
+    pub fn new(seed: i32) -> Self {
+        NoiseSettings {
+            seed,
+            noise_type: None,
+            frequency: None,
+            fractal_type: None,
+            octaves: None,
+            lacunarity: None,
+            gain: None,
+        }
+    }
+
+    pub fn build(&self) -> FastNoiseLite {
+        let mut noise = FastNoiseLite::with_seed(self.seed);
+        if let Some(noise_type) = self.noise_type {
+            noise.set_noise_type(Some(noise_type.into()));
+        }
+        if let Some(fractal_type) = self.fractal_type {
+            noise.set_fractal_type(Some(fractal_type.into()));
+        }
+        if self.octaves.is_some() {
+            noise.set_fractal_octaves(self.octaves);
+        }
+        if self.lacunarity.is_some() {
+            noise.set_fractal_lacunarity(self.lacunarity);
+        }
+        if self.gain.is_some() {
+            noise.set_fractal_gain(self.gain);
+        }
+        if self.frequency.is_some() {
+            noise.set_frequency(self.frequency);
+        }
+        noise
+    }
+
+    // Configuraciones predefinidas, accesibles por nombre desde los archivos de escena
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "generic" => Some(Self::generic()),
+            "icy" => Some(Self::icy()),
+            "gas_giant" => Some(Self::gas_giant()),
+            "cloud" => Some(Self::cloud()),
+            "ground" => Some(Self::ground()),
+            "lava" => Some(Self::lava()),
+            "nebula" => Some(Self::nebula()),
+            _ => None,
+        }
+    }
+
+    pub fn preset_names() -> &'static [&'static str] {
+        &["generic", "icy", "gas_giant", "cloud", "ground", "lava", "nebula"]
+    }
+
+    pub fn generic() -> Self {
+        NoiseSettings {
+            noise_type: Some(NoiseKind::Perlin),  // Usar Perlin por defecto
+            frequency: Some(0.05),                // Frecuencia básica
+            ..NoiseSettings::new(1337)
+        }
+    }
+
+    pub fn icy() -> Self {
+        NoiseSettings {
+            noise_type: Some(NoiseKind::OpenSimplex2), // Simplex para suaves transiciones
+            frequency: Some(0.08),                     // Frecuencia más alta
+            fractal_type: Some(FractalKind::Fbm),      // Más octavas para textura
+            ..NoiseSettings::new(7890)
+        }
+    }
+
+    pub fn gas_giant() -> Self {
+        NoiseSettings {
+            noise_type: Some(NoiseKind::OpenSimplex2), // Efecto de bandas suaves
+            frequency: Some(0.02),                     // Características grandes
+            ..NoiseSettings::new(4242)
+        }
+    }
+
+    pub fn cloud() -> Self {
+        NoiseSettings {
+            noise_type: Some(NoiseKind::OpenSimplex2),
+            ..NoiseSettings::new(1337)
+        }
+    }
+
+    pub fn ground() -> Self {
+        // Use FBm fractal type to layer multiple octaves of noise
+        NoiseSettings {
+            noise_type: Some(NoiseKind::Cellular), // Cellular noise for cracks
+            fractal_type: Some(FractalKind::Fbm),  // Fractal Brownian Motion
+            octaves: Some(5),                      // More octaves = more detail
+            lacunarity: Some(2.0),                 // Lacunarity controls frequency scaling
+            gain: Some(0.5),                       // Gain controls amplitude scaling
+            frequency: Some(0.05),                 // Lower frequency for larger features
+            ..NoiseSettings::new(1337)
+        }
+    }
+
+    pub fn lava() -> Self {
+        // Use FBm for multi-layered noise, giving a "turbulent" feel
+        NoiseSettings {
+            noise_type: Some(NoiseKind::Perlin),  // Perlin noise for smooth, natural texture
+            fractal_type: Some(FractalKind::Fbm), // FBm for layered detail
+            octaves: Some(6),                     // High octaves for rich detail
+            lacunarity: Some(2.0),                // Higher lacunarity = more contrast between layers
+            gain: Some(0.5),                      // Higher gain = more influence of smaller details
+            frequency: Some(0.002),               // Low frequency = large features
+            ..NoiseSettings::new(42)
+        }
+    }
+
+    // Para el cielo: se evalúa sobre direcciones unitarias, por eso la frecuencia es alta
+    pub fn nebula() -> Self {
+        NoiseSettings {
+            noise_type: Some(NoiseKind::OpenSimplex2),
+            fractal_type: Some(FractalKind::Fbm),
+            octaves: Some(5),
+            frequency: Some(1.2),
+            ..NoiseSettings::new(2024)
+        }
+    }
+}
+
+pub fn create_noise_for_planet(index: usize) -> FastNoiseLite {
+    match index {
+        0 => create_lava_noise(),
+        1 => create_gas_giant_noise(),
+        2 => create_generic_noise(),
+        3 => create_ground_noise(),
+        4 => create_cloud_noise(),
+        5 => create_icy_noise(),
+        _ => create_generic_noise(),
+    }
+}
+
+pub fn create_generic_noise() -> FastNoiseLite {
+    NoiseSettings::generic().build()
+}
+
+pub fn create_icy_noise() -> FastNoiseLite {
+    NoiseSettings::icy().build()
+}
+
+pub fn create_gas_giant_noise() -> FastNoiseLite {
+    NoiseSettings::gas_giant().build()
+}
+
+pub fn create_cloud_noise() -> FastNoiseLite {
+    NoiseSettings::cloud().build()
+}
+
+pub fn create_ground_noise() -> FastNoiseLite {
+    NoiseSettings::ground().build()
+}
+
+pub fn create_lava_noise() -> FastNoiseLite {
+    NoiseSettings::lava().build()
+}
@@ -1,11 +1,18 @@
 // obj.rs
 
-use tobj;
 use nalgebra_glm::{Vec2, Vec3};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use crate::material::{Material, NO_MATERIAL};
 use crate::vertex::Vertex;
 
 pub struct Obj {
     meshes: Vec<Mesh>,
+    materials: Vec<Material>,
+    // Problemas que no impiden usar el modelo, como un .mtl que no existe
+    warnings: Vec<String>,
 }
 
 struct Mesh {
@@ -13,15 +20,40 @@ struct Mesh {
     normals: Vec<Vec3>,
     texcoords: Vec<Vec2>,
     indices: Vec<u32>,
+    material: Option<usize>,
 }
 
 impl Obj {
+    // Las bibliotecas de materiales (mtllib) se buscan junto al OBJ. Si una falta, el
+    // modelo se carga igual con el material por defecto y queda un aviso en `warnings`.
     pub fn load(filename: &str) -> Result<Self, tobj::LoadError> {
-        let (models, _) = tobj::load_obj(filename, &tobj::LoadOptions {
-            single_index: true,
-            triangulate: true,
-            ..Default::default()
-        })?;
+        let file = File::open(filename).map_err(|_| tobj::LoadError::OpenFileFailed)?;
+        let directory = Path::new(filename).parent().unwrap_or(Path::new(""));
+        // tobj pide un Fn para cargar las bibliotecas, así que los avisos van en un RefCell
+        let library_warnings = RefCell::new(Vec::new());
+
+        let (models, materials) = tobj::load_obj_buf(
+            &mut BufReader::new(file),
+            &tobj::LoadOptions {
+                single_index: true,
+                triangulate: true,
+                ..Default::default()
+            },
+            |library| {
+                let path = directory.join(library);
+                tobj::load_mtl(&path).inspect_err(|error| {
+                    library_warnings.borrow_mut().push(format!("{}: failed to load material library {}: {}", filename, path.display(), error));
+                })
+            },
+        )?;
+        let mut warnings = library_warnings.into_inner();
+
+        // Sin ninguna biblioteca cargada tobj devuelve el error, que ya quedó en los avisos
+        let materials = materials
+            .unwrap_or_default()
+            .iter()
+            .map(|material| Material::from_mtl(material, directory, &mut warnings))
+            .collect();
 
         let meshes = models.into_iter().map(|model| {
             let mesh = model.mesh;
@@ -36,16 +68,30 @@ impl Obj {
                     .map(|t| Vec2::new(t[0], 1.0 - t[1]))
                     .collect(),
                 indices: mesh.indices,
+                material: mesh.material_id,
             }
         }).collect();
 
-        Ok(Obj { meshes })
+        Ok(Obj { meshes, materials, warnings })
+    }
+
+    // Materiales en el orden de `Vertex::material`
+    pub fn materials(&self) -> &[Material] {
+        &self.materials
+    }
+
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
     }
 
     pub fn get_vertex_array(&self) -> Vec<Vertex> {
         let mut vertices = Vec::new();
 
         for mesh in &self.meshes {
+            let material = mesh.material
+                .filter(|&index| index < self.materials.len())
+                .map_or(NO_MATERIAL, |index| index as u32);
+
             for &index in &mesh.indices {
                 let position = mesh.vertices[index as usize];
                 let normal = mesh.normals.get(index as usize)
@@ -55,7 +101,9 @@ impl Obj {
                     .cloned()
                     .unwrap_or(Vec2::new(0.0, 0.0));
 
-                vertices.push(Vertex::new(position, normal, tex_coords));
+                let mut vertex = Vertex::new(position, normal, tex_coords);
+                vertex.material = material;
+                vertices.push(vertex);
             }
         }
 
@@ -0,0 +1,85 @@
+// orbit.rs
+
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+
+// Órbita kepleriana alrededor de un cuerpo padre. El plano de referencia es XZ (Y hacia arriba);
+// `period` se mide en las mismas unidades que Uniforms.time (frames).
+#[derive(Debug, Clone, Copy)]
+pub struct Orbit {
+    pub parent: usize,
+    pub semi_major_axis: f32,
+    pub eccentricity: f32,
+    pub inclination: f32,
+    pub period: f32,
+    pub phase: f32,
+}
+
+impl Orbit {
+    pub fn circular(parent: usize, radius: f32, period: f32) -> Self {
+        Orbit {
+            parent,
+            semi_major_axis: radius,
+            eccentricity: 0.0,
+            inclination: 0.0,
+            period,
+            phase: 0.0,
+        }
+    }
+
+    pub fn with_eccentricity(mut self, eccentricity: f32) -> Self {
+        self.eccentricity = eccentricity;
+        self
+    }
+
+    pub fn with_inclination(mut self, inclination: f32) -> Self {
+        self.inclination = inclination;
+        self
+    }
+
+    pub fn with_phase(mut self, phase: f32) -> Self {
+        self.phase = phase;
+        self
+    }
+
+    // Anomalía media en el tiempo dado; `phase` es la anomalía media en time = 0
+    pub fn mean_anomaly(&self, time: f32) -> f32 {
+        if self.period == 0.0 {
+            return self.phase;
+        }
+        (self.phase + 2.0 * PI * time / self.period).rem_euclid(2.0 * PI)
+    }
+
+    // Posición relativa al cuerpo padre; el periapsis está sobre el eje +X
+    pub fn position(&self, time: f32) -> Vec3 {
+        let e = self.eccentricity;
+        let a = self.semi_major_axis;
+        let eccentric_anomaly = solve_kepler(self.mean_anomaly(time), e);
+
+        let (sin_e, cos_e) = eccentric_anomaly.sin_cos();
+        let x = a * (cos_e - e);
+        let z = a * (1.0 - e * e).sqrt() * sin_e;
+
+        // Inclinación: rotación del plano orbital alrededor del eje X
+        let (sin_i, cos_i) = self.inclination.sin_cos();
+        Vec3::new(x, z * sin_i, z * cos_i)
+    }
+}
+
+// Resuelve la ecuación de Kepler M = E - e·sin(E) para la anomalía excéntrica E (Newton-Raphson)
+pub fn solve_kepler(mean_anomaly: f32, eccentricity: f32) -> f32 {
+    let e = eccentricity.clamp(0.0, 0.99);
+    let mut eccentric_anomaly = if e < 0.8 { mean_anomaly } else { PI };
+
+    for _ in 0..16 {
+        let f = eccentric_anomaly - e * eccentric_anomaly.sin() - mean_anomaly;
+        let derivative = 1.0 - e * eccentric_anomaly.cos();
+        let step = f / derivative;
+        eccentric_anomaly -= step;
+        if step.abs() < 1e-6 {
+            break;
+        }
+    }
+
+    eccentric_anomaly
+}
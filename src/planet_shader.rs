@@ -0,0 +1,199 @@
+// planet_shader.rs
+
+use std::fmt;
+use crate::blend::BlendMode;
+use crate::color::Color;
+use crate::fragment::Fragment;
+use crate::renderer::Uniforms;
+use crate::shaders::vertex_shader;
+use crate::vertex::Vertex;
+
+// Valor de un parámetro ajustable de un shader
+#[derive(Debug, Clone, Copy)]
+pub enum ParamValue {
+    Float(f32),
+    Color(Color),
+}
+
+impl ParamValue {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ParamValue::Float(_) => "float",
+            ParamValue::Color(_) => "color",
+        }
+    }
+
+    pub fn as_float(self, name: &str) -> Result<f32, ParameterError> {
+        match self {
+            ParamValue::Float(value) => Ok(value),
+            _ => Err(ParameterError::WrongType { name: name.to_string(), expected: "float" }),
+        }
+    }
+
+    pub fn as_color(self, name: &str) -> Result<Color, ParameterError> {
+        match self {
+            ParamValue::Color(color) => Ok(color),
+            _ => Err(ParameterError::WrongType { name: name.to_string(), expected: "color" }),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShaderParameter {
+    pub name: &'static str,
+    pub value: ParamValue,
+}
+
+impl ShaderParameter {
+    pub fn float(name: &'static str, value: f32) -> Self {
+        ShaderParameter { name, value: ParamValue::Float(value) }
+    }
+
+    pub fn color(name: &'static str, value: Color) -> Self {
+        ShaderParameter { name, value: ParamValue::Color(value) }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParameterError {
+    Unknown { shader: String, name: String },
+    WrongType { name: String, expected: &'static str },
+    // Un valor de un archivo que no es un número ni un color válido
+    Invalid { name: String, message: String },
+}
+
+impl fmt::Display for ParameterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParameterError::Unknown { shader, name } => {
+                write!(f, "shader '{}' has no parameter named '{}'", shader, name)
+            }
+            ParameterError::WrongType { name, expected } => {
+                write!(f, "parameter '{}' expects a {} value", name, expected)
+            }
+            ParameterError::Invalid { name, message } => write!(f, "parameter '{}': {}", name, message),
+        }
+    }
+}
+
+impl std::error::Error for ParameterError {}
+
+// Un shader de planeta: etapa de vértices, etapa de fragmentos y sus parámetros ajustables.
+// Debe ser Sync porque los tiles se sombrean en paralelo con el mismo shader.
+pub trait PlanetShader: Send + Sync {
+    fn name(&self) -> &str;
+
+    fn vertex(&self, vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
+        vertex_shader(vertex, uniforms)
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color;
+
+    // Si el shader lee `Fragment::intensity`. Con false el renderer no calcula las luces ni
+    // las sombras antes de llamar a `fragment` y la intensidad queda en 1.
+    fn needs_intensity(&self) -> bool {
+        true
+    }
+
+    // Si el shader lee las derivadas de pantalla del fragmento (`tex_coords_dx`,
+    // `vertex_position_dx`, ...), p. ej. para elegir el mipmap. Con false el rasterizador
+    // no las calcula y quedan en cero.
+    fn needs_derivatives(&self) -> bool {
+        false
+    }
+
+    // Modo de mezcla del material; los cuerpos pueden cambiarlo en el archivo de escena
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::Opaque
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        Vec::new()
+    }
+
+    fn set_parameter(&mut self, name: &str, _value: ParamValue) -> Result<(), ParameterError> {
+        Err(ParameterError::Unknown { shader: self.name().to_string(), name: name.to_string() })
+    }
+
+    // Comprueba sin cambiar nada que `set_parameter` aceptaría el valor: que el parámetro
+    // exista en `parameters` y que el valor sea del mismo tipo. Un shader cuyo
+    // `set_parameter` rechace otros valores debe rechazarlos también aquí.
+    fn validate_parameter(&self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        let parameter = self
+            .parameters()
+            .into_iter()
+            .find(|parameter| parameter.name == name)
+            .ok_or_else(|| ParameterError::Unknown { shader: self.name().to_string(), name: name.to_string() })?;
+        match parameter.value {
+            ParamValue::Float(_) => value.as_float(name).map(drop),
+            ParamValue::Color(_) => value.as_color(name).map(drop),
+        }
+    }
+}
+
+type ShaderFactory = Box<dyn Fn() -> Box<dyn PlanetShader>>;
+
+// Registro de shaders por nombre. Guarda constructores para que cada planeta pueda
+// tener su propia instancia con sus propios parámetros.
+#[derive(Default)]
+pub struct ShaderRegistry {
+    entries: Vec<(String, ShaderFactory)>,
+}
+
+impl ShaderRegistry {
+    pub fn new() -> Self {
+        ShaderRegistry { entries: Vec::new() }
+    }
+
+    // Registro con todos los shaders incluidos en el crate
+    pub fn with_builtin_shaders() -> Self {
+        let mut registry = ShaderRegistry::new();
+        crate::shaders::register_builtin_shaders(&mut registry);
+        registry
+    }
+
+    // Registra un shader; si ya existe uno con el mismo nombre se reemplaza
+    pub fn register<F>(&mut self, factory: F)
+    where
+        F: Fn() -> Box<dyn PlanetShader> + 'static,
+    {
+        let name = factory().name().to_string();
+        let factory: ShaderFactory = Box::new(factory);
+        match self.entries.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(entry) => entry.1 = factory,
+            None => self.entries.push((name, factory)),
+        }
+    }
+
+    pub fn create(&self, name: &str) -> Option<Box<dyn PlanetShader>> {
+        self.entries
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, factory)| factory())
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.entries.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.entries.iter().position(|(existing, _)| existing == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Nombre del shader que sigue a `name`, dando la vuelta al final de la lista
+    pub fn next_name(&self, name: &str) -> Option<&str> {
+        if self.entries.is_empty() {
+            return None;
+        }
+        let next = self.index_of(name).map_or(0, |index| (index + 1) % self.entries.len());
+        Some(self.entries[next].0.as_str())
+    }
+}
@@ -0,0 +1,429 @@
+// renderer.rs
+
+use nalgebra_glm::{Mat4, Vec3, Vec4};
+use std::sync::Arc;
+use std::thread;
+use fastnoise_lite::FastNoiseLite;
+
+use crate::antialiasing::AntiAliasing;
+use crate::blend::BlendMode;
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::clipping::{clip_to_frustum, project_to_screen};
+use crate::culling::{is_culled, CullMode, FrontFace};
+use crate::framebuffer::Framebuffer;
+use crate::light::{self, Light, LightKind};
+use crate::material::Material;
+use crate::matrix::{create_perspective_matrix, create_view_matrix, create_viewport_matrix};
+use crate::noise::create_generic_noise;
+use crate::planet_shader::PlanetShader;
+use crate::scene::{Body, Scene};
+use crate::shadow::{CubeShadowMap, ShadowCaster, ShadowSettings};
+use crate::skybox::Skybox;
+use crate::texture::{Texture, Textures};
+use crate::tiles::render_tiles;
+use crate::fragment::Fragment;
+use crate::triangle::{rasterize_in, FragmentSink, Interpolation, PixelRect};
+use crate::vertex::Vertex;
+
+pub struct Uniforms {
+    pub model_matrix: Mat4,
+    pub view_matrix: Mat4,
+    pub projection_matrix: Mat4,
+    pub viewport_matrix: Mat4,
+    pub time: u32,
+    pub noise: Arc<FastNoiseLite>,
+    // Texturas del cuerpo que se está dibujando
+    pub textures: Arc<Textures>,
+    // Materiales del cuerpo que se está dibujando (ver `Fragment::material`)
+    pub materials: Arc<Vec<Material>>,
+    // Luces en espacio de mundo y posición de la cámara, para iluminar en el fragment shader
+    pub lights: Vec<Light>,
+    pub camera_position: Vec3,
+    // Mapa de sombras de cada luz (mismo índice que `lights`); None si la luz no tiene
+    pub shadow_maps: Vec<Option<CubeShadowMap>>,
+    pub shadow_settings: ShadowSettings,
+}
+
+impl Uniforms {
+    pub fn texture(&self, name: &str) -> Option<&Texture> {
+        self.textures.get(name).map(|texture| texture.as_ref())
+    }
+
+    pub fn material(&self, index: u32) -> Option<&Material> {
+        self.materials.get(index as usize)
+    }
+
+    // Fracción de la luz `index` que llega a `position` (1 = sin sombra), con filtrado PCF
+    pub fn shadow(&self, index: usize, position: &Vec3) -> f32 {
+        match self.shadow_maps.get(index) {
+            Some(Some(map)) if self.shadow_settings.enabled => {
+                map.visibility(position, self.shadow_settings.bias, self.shadow_settings.pcf_radius)
+            }
+            _ => 1.0,
+        }
+    }
+
+    // Igual que `Fragment::intensity`: luz difusa de todas las luces sin su color, con sombras
+    pub fn diffuse_intensity(&self, fragment: &Fragment) -> f32 {
+        light::diffuse_intensity(&self.lights, &fragment.world_position, &fragment.normal, |index| {
+            self.shadow(index, &fragment.world_position)
+        })
+    }
+
+    // Luz difusa (Lambert) con el color de cada luz y sus sombras
+    pub fn diffuse(&self, fragment: &Fragment) -> Color {
+        light::diffuse(&self.lights, &fragment.world_position, &fragment.normal, |index| {
+            self.shadow(index, &fragment.world_position)
+        })
+    }
+
+    // Brillo especular (Blinn-Phong) visto desde la cámara; las sombras también lo apagan
+    pub fn specular(&self, fragment: &Fragment, shininess: f32) -> Color {
+        light::specular(&self.lights, &fragment.world_position, &fragment.normal, &self.camera_position, shininess, |index| {
+            self.shadow(index, &fragment.world_position)
+        })
+    }
+}
+
+// Ajustes del rasterizado para una llamada a render
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RasterState {
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    pub interpolation: Interpolation,
+    // Con un modo distinto de Opaque los triángulos se dibujan de atrás hacia adelante
+    // y no escriben profundidad
+    pub blend: BlendMode,
+    // Hilos para sombrear los tiles; con 1 se rasteriza en el hilo actual sin tiles
+    pub threads: usize,
+}
+
+impl Default for RasterState {
+    fn default() -> Self {
+        RasterState {
+            cull_mode: CullMode::default(),
+            front_face: FrontFace::default(),
+            interpolation: Interpolation::default(),
+            blend: BlendMode::default(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+// Vértices transformados y triángulos en pantalla de una llamada a render. El Renderer
+// los conserva entre frames para no volver a reservar memoria en cada uno.
+#[derive(Default)]
+pub struct RenderBuffers {
+    vertices: Vec<Vertex>,
+    triangles: Vec<[Vertex; 3]>,
+}
+
+// Pipeline completo: vertex shader -> ensamblado de triángulos -> rasterizado -> fragment shader -> framebuffer
+pub fn render(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    vertex_array: &[Vertex],
+    shader: &dyn PlanetShader,
+    state: &RasterState,
+    buffers: &mut RenderBuffers,
+) {
+    let RenderBuffers { vertices, triangles } = buffers;
+    vertices.clear();
+    vertices.extend(vertex_array.iter().map(|vertex| shader.vertex(vertex, uniforms)));
+
+    // Recorte contra el frustum antes de la división de perspectiva; un triángulo
+    // recortado puede convertirse en varios
+    let screen_triangles = vertices.chunks_exact(3).flat_map(|tri| {
+        clip_to_frustum(&tri[0], &tri[1], &tri[2]).into_iter().filter_map(|mut clipped| {
+            for vertex in &mut clipped {
+                project_to_screen(vertex, &uniforms.viewport_matrix);
+            }
+
+            let [a, b, c] = [0, 1, 2].map(|i| clipped[i].transformed_position);
+            (!is_culled(&a, &b, &c, state.cull_mode, state.front_face)).then_some(clipped)
+        })
+    });
+
+    // Los tiles y las superficies transparentes necesitan todos los triángulos a la vez
+    if state.threads > 1 || !state.blend.is_opaque() {
+        triangles.clear();
+        triangles.extend(screen_triangles);
+        if !state.blend.is_opaque() {
+            sort_back_to_front(triangles);
+        }
+        if state.threads > 1 {
+            render_tiles(framebuffer, uniforms, triangles, shader, state);
+        } else {
+            render_in_place(framebuffer, uniforms, triangles.iter().cloned(), shader, state);
+        }
+    } else {
+        render_in_place(framebuffer, uniforms, screen_triangles, shader, state);
+    }
+}
+
+// Rasteriza y sombrea cada triángulo de inmediato sobre el framebuffer completo
+pub(crate) fn render_in_place(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    triangles: impl IntoIterator<Item = [Vertex; 3]>,
+    shader: &dyn PlanetShader,
+    state: &RasterState,
+) {
+    let antialiasing = framebuffer.antialiasing();
+    let rect = PixelRect { min_x: 0, min_y: 0, max_x: framebuffer.width as i32 - 1, max_y: framebuffer.height as i32 - 1 };
+    let samples = framebuffer.samples();
+    let (color, depth) = framebuffer.sample_targets();
+    let mut sink = ShadingSink { color, depth, rect, samples, blend: state.blend, uniforms, shader };
+    for [v1, v2, v3] in triangles {
+        rasterize_in(&v1, &v2, &v3, state.interpolation, antialiasing, &rect, &mut sink);
+    }
+}
+
+// Ordena por la profundidad media en pantalla, el más lejano primero. El orden es
+// estable para que los empates se dibujen igual en cada frame.
+fn sort_back_to_front(triangles: &mut [[Vertex; 3]]) {
+    let depth = |tri: &[Vertex; 3]| tri.iter().map(|v| v.transformed_position.z).sum::<f32>();
+    triangles.sort_by(|a, b| depth(b).total_cmp(&depth(a)));
+}
+
+// Sombrea cada fragmento directamente sobre un bloque de color y profundidad por muestra
+// (el framebuffer completo o la copia de un tile); las muestras que quedan detrás del
+// zbuffer se descartan sin llamar al fragment shader. Los fragmentos transparentes se
+// mezclan con el color de la muestra y dejan la profundidad como estaba.
+pub(crate) struct ShadingSink<'a> {
+    pub color: &'a mut [Color],
+    pub depth: &'a mut [f32],
+    pub rect: PixelRect,
+    pub samples: usize,
+    pub blend: BlendMode,
+    pub uniforms: &'a Uniforms,
+    pub shader: &'a dyn PlanetShader,
+}
+
+impl ShadingSink<'_> {
+    // Índice de la primera muestra del pixel (x, y)
+    fn base(&self, x: i32, y: i32) -> usize {
+        ((y - self.rect.min_y) as usize * self.rect.width() + (x - self.rect.min_x) as usize) * self.samples
+    }
+}
+
+impl FragmentSink for ShadingSink<'_> {
+    fn test(&mut self, x: i32, y: i32, coverage: u32, sample_depths: &[f32]) -> u32 {
+        if !self.rect.contains(x, y) {
+            return 0;
+        }
+
+        let base = self.base(x, y);
+        let mut visible = 0;
+        for (s, &depth) in sample_depths.iter().enumerate() {
+            if coverage & (1 << s) != 0 && self.depth[base + s] > depth {
+                visible |= 1 << s;
+            }
+        }
+        visible
+    }
+
+    fn needs_derivatives(&self) -> bool {
+        self.shader.needs_derivatives()
+    }
+
+    fn emit(&mut self, mut fragment: Fragment) {
+        let base = self.base(fragment.position.x as i32, fragment.position.y as i32);
+        if self.shader.needs_intensity() {
+            fragment.intensity = self.uniforms.diffuse_intensity(&fragment);
+        }
+        let shaded_color = self.shader.fragment(&fragment, self.uniforms);
+
+        for s in 0..self.samples {
+            if fragment.coverage & (1 << s) == 0 {
+                continue;
+            }
+            if self.blend.is_opaque() {
+                self.color[base + s] = shaded_color;
+                self.depth[base + s] = fragment.sample_depths[s];
+            } else {
+                self.color[base + s] = self.blend.apply(self.color[base + s], shaded_color);
+            }
+        }
+    }
+}
+
+// Dueño del framebuffer y de los uniforms; cada llamada a draw pasa un modelo por el pipeline
+pub struct Renderer {
+    pub framebuffer: Framebuffer,
+    pub uniforms: Uniforms,
+    pub raster: RasterState,
+    buffers: RenderBuffers,
+}
+
+impl Renderer {
+    pub fn new(width: usize, height: usize) -> Self {
+        Renderer {
+            framebuffer: Framebuffer::new(width, height),
+            uniforms: Uniforms {
+                model_matrix: Mat4::identity(),
+                view_matrix: Mat4::identity(),
+                projection_matrix: create_perspective_matrix(width as f32, height as f32),
+                viewport_matrix: create_viewport_matrix(width as f32, height as f32),
+                time: 0,
+                noise: Arc::new(create_generic_noise()),
+                textures: Arc::new(Textures::new()),
+                materials: Arc::new(Vec::new()),
+                lights: vec![Light::headlight()],
+                camera_position: Vec3::new(0.0, 0.0, 0.0),
+                shadow_maps: Vec::new(),
+                shadow_settings: ShadowSettings::default(),
+            },
+            raster: RasterState::default(),
+            buffers: RenderBuffers::default(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.framebuffer.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.framebuffer.height
+    }
+
+    pub fn clear(&mut self) {
+        self.framebuffer.clear();
+    }
+
+    pub fn set_camera(&mut self, camera: &Camera) {
+        self.uniforms.view_matrix = create_view_matrix(camera.eye, camera.center, camera.up);
+        self.uniforms.camera_position = camera.eye;
+    }
+
+    pub fn set_antialiasing(&mut self, antialiasing: AntiAliasing) {
+        self.framebuffer.set_antialiasing(antialiasing);
+    }
+
+    pub fn set_time(&mut self, time: u32) {
+        self.uniforms.time = time;
+    }
+
+    // Usa el RasterState del renderer; con anti-aliasing deja `buffer` ya resuelto
+    pub fn draw(&mut self, vertex_array: &[Vertex], model_matrix: Mat4, noise: &Arc<FastNoiseLite>, shader: &dyn PlanetShader) {
+        self.uniforms.model_matrix = model_matrix;
+        self.uniforms.noise = Arc::clone(noise);
+        render(&mut self.framebuffer, &self.uniforms, vertex_array, shader, &self.raster, &mut self.buffers);
+        self.framebuffer.resolve();
+    }
+
+    // Dibuja todos los cuerpos de la escena sobre el mismo framebuffer; el zbuffer
+    // compartido resuelve qué planeta queda delante. El culling y la mezcla son los de
+    // cada cuerpo: primero los opacos, luego el cielo detrás de ellos y al final los
+    // transparentes, del más lejano al más cercano a la cámara. Las luces de la escena
+    // reemplazan las de los uniforms.
+    pub fn draw_scene(&mut self, scene: &Scene) {
+        if !scene.lights.is_empty() {
+            self.uniforms.lights = scene.lights();
+        }
+        self.update_shadow_maps(scene);
+
+        let (opaque, mut transparent): (Vec<&Body>, Vec<&Body>) =
+            scene.bodies.iter().partition(|body| body.blend_mode().is_opaque());
+
+        let view_depth = |body: &Body| (self.uniforms.view_matrix * Vec4::new(body.position.x, body.position.y, body.position.z, 1.0)).z;
+        transparent.sort_by(|a, b| view_depth(a).total_cmp(&view_depth(b)));
+
+        for body in opaque {
+            self.draw_body(body);
+        }
+        if let Some(sky) = &scene.sky {
+            self.draw_sky(sky.as_ref());
+        }
+        for body in transparent {
+            self.draw_body(body);
+        }
+        self.framebuffer.resolve();
+    }
+
+    fn draw_body(&mut self, body: &Body) {
+        self.uniforms.model_matrix = body.model_matrix();
+        self.uniforms.noise = Arc::clone(&body.noise);
+        self.uniforms.textures = Arc::clone(&body.textures);
+        self.uniforms.materials = Arc::clone(&body.materials);
+        let state = RasterState {
+            cull_mode: body.cull_mode,
+            front_face: body.front_face,
+            blend: body.blend_mode(),
+            ..self.raster
+        };
+        render(&mut self.framebuffer, &self.uniforms, &body.mesh, body.shader.as_ref(), &state, &mut self.buffers);
+    }
+
+    // Pinta el cielo en las muestras que no cubrió ningún cuerpo. La dirección de cada pixel
+    // sale de invertir la proyección y la vista, así que sigue las rotaciones de la cámara
+    // (orbit, pan) pero no su posición. No hace resolve.
+    pub fn draw_sky(&mut self, sky: &dyn Skybox) {
+        let (Some(inverse_projection), Some(inverse_view)) =
+            (self.uniforms.projection_matrix.try_inverse(), self.uniforms.view_matrix.try_inverse())
+        else {
+            return;
+        };
+        let (width, height) = (self.width() as f32, self.height() as f32);
+        // Rayo (sin normalizar) que pasa por el punto (x, y) de la pantalla en el plano lejano
+        let ray = |x: f32, y: f32| {
+            let ndc = Vec4::new(x / width * 2.0 - 1.0, 1.0 - y / height * 2.0, 1.0, 1.0);
+            let view = inverse_projection * ndc;
+            (inverse_view * Vec4::new(view.x / view.w, view.y / view.w, view.z / view.w, 0.0)).xyz()
+        };
+
+        // En el plano lejano el rayo es lineal en (x, y): basta con el origen y dos pasos
+        let origin = ray(0.5, 0.5);
+        let (step_x, step_y) = (ray(1.5, 0.5) - origin, ray(0.5, 1.5) - origin);
+        let (center_x, center_y) = (width / 2.0, height / 2.0);
+        let pixel_angle = ray(center_x, center_y).angle(&ray(center_x, center_y + 1.0));
+
+        let time = self.uniforms.time;
+        self.framebuffer.fill_background(self.raster.threads, |x, y| {
+            let direction = (origin + step_x * x as f32 + step_y * y as f32).normalize();
+            sky.sample(&direction, pixel_angle, time)
+        });
+    }
+
+    // Renderiza el cube map de cada luz puntual de la escena con los cuerpos opacos que
+    // proyectan sombra, sin el cuerpo en el que está la luz (el sol la taparía por completo).
+    // Los mapas se vuelven a renderizar en cada frame porque los cuerpos se mueven; solo se
+    // conserva su memoria mientras la luz no cambie de posición ni de tamaño de mapa.
+    fn update_shadow_maps(&mut self, scene: &Scene) {
+        let settings = self.uniforms.shadow_settings;
+        if !settings.enabled || scene.lights.is_empty() {
+            self.uniforms.shadow_maps.clear();
+            return;
+        }
+
+        let mut maps = std::mem::take(&mut self.uniforms.shadow_maps);
+        maps.resize_with(scene.lights.len(), || None);
+        for (map, scene_light) in maps.iter_mut().zip(&scene.lights) {
+            let LightKind::Point { position } = scene_light.light.kind else {
+                *map = None;
+                continue;
+            };
+
+            let casters: Vec<ShadowCaster> = scene
+                .bodies
+                .iter()
+                .enumerate()
+                .filter(|&(index, body)| Some(index) != scene_light.body && body.casts_shadows && body.blend_mode().is_opaque())
+                .map(|(_, body)| ShadowCaster {
+                    mesh: &body.mesh,
+                    model_matrix: body.model_matrix(),
+                    cull_mode: body.cull_mode,
+                    front_face: body.front_face,
+                })
+                .collect();
+
+            let map = match map {
+                Some(existing) if existing.light_position() == position && existing.size() == settings.size => existing,
+                _ => map.insert(CubeShadowMap::new(position, &settings)),
+            };
+            map.render(&casters, self.raster.threads);
+        }
+        self.uniforms.shadow_maps = maps;
+    }
+}
@@ -0,0 +1,266 @@
+// scene.rs
+
+use nalgebra_glm::{Vec3, Mat4};
+use std::f32::consts::PI;
+use std::fmt;
+use std::sync::Arc;
+use fastnoise_lite::FastNoiseLite;
+
+use crate::blend::BlendMode;
+use crate::culling::{CullMode, FrontFace};
+use crate::light::{Light, LightKind};
+use crate::material::Material;
+use crate::matrix::create_model_matrix;
+use crate::orbit::Orbit;
+use crate::planet_shader::{PlanetShader, ShaderParameter};
+use crate::skybox::Skybox;
+use crate::texture::{Texture, Textures};
+use crate::vertex::Vertex;
+
+// Un cuerpo celeste: malla, transformación, shader, ruido, texturas y materiales propios
+pub struct Body {
+    pub name: String,
+    pub mesh: Arc<Vec<Vertex>>,
+    pub position: Vec3,
+    pub scale: f32,
+    pub rotation: Vec3,
+    pub orbit: Option<Orbit>,
+    pub axial_tilt: f32,
+    pub spin_period: f32,
+    pub spin_angle: f32,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    // None usa el modo de mezcla del shader
+    pub blend: Option<BlendMode>,
+    // Si el cuerpo aparece en los mapas de sombras de las luces
+    pub casts_shadows: bool,
+    pub shader: Box<dyn PlanetShader>,
+    pub noise: Arc<FastNoiseLite>,
+    // Texturas por nombre; el shader las lee con `uniforms.texture(nombre)`
+    pub textures: Arc<Textures>,
+    // Materiales del OBJ, en el orden de `Vertex::material`
+    pub materials: Arc<Vec<Material>>,
+    // Shader con el que se creó el cuerpo y sus parámetros en ese momento. Un archivo de
+    // parámetros parte de estos valores en cada recarga.
+    declared_shader: String,
+    base_parameters: Vec<ShaderParameter>,
+}
+
+impl Body {
+    pub fn new(name: &str, mesh: Arc<Vec<Vertex>>, shader: Box<dyn PlanetShader>, noise: Arc<FastNoiseLite>) -> Self {
+        Body {
+            name: name.to_string(),
+            mesh,
+            position: Vec3::new(0.0, 0.0, 0.0),
+            scale: 1.0,
+            rotation: Vec3::new(0.0, 0.0, 0.0),
+            orbit: None,
+            axial_tilt: 0.0,
+            spin_period: 0.0,
+            spin_angle: 0.0,
+            cull_mode: CullMode::default(),
+            front_face: FrontFace::default(),
+            blend: None,
+            casts_shadows: true,
+            noise,
+            textures: Arc::new(Textures::new()),
+            materials: Arc::new(Vec::new()),
+            declared_shader: shader.name().to_string(),
+            base_parameters: shader.parameters(),
+            shader,
+        }
+    }
+
+    pub fn with_position(mut self, position: Vec3) -> Self {
+        self.position = position;
+        self
+    }
+
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+
+    pub fn with_rotation(mut self, rotation: Vec3) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_orbit(mut self, orbit: Orbit) -> Self {
+        self.orbit = Some(orbit);
+        self
+    }
+
+    // Inclinación del eje (radianes) y periodo de rotación propia (frames)
+    pub fn with_spin(mut self, axial_tilt: f32, spin_period: f32) -> Self {
+        self.axial_tilt = axial_tilt;
+        self.spin_period = spin_period;
+        self
+    }
+
+    // Los modelos abiertos o de doble cara (como las naves) necesitan CullMode::None
+    pub fn with_culling(mut self, cull_mode: CullMode, front_face: FrontFace) -> Self {
+        self.cull_mode = cull_mode;
+        self.front_face = front_face;
+        self
+    }
+
+    // Reemplaza el modo de mezcla del shader (por ejemplo, una capa de nubes aditiva)
+    pub fn with_blend(mut self, blend: BlendMode) -> Self {
+        self.blend = Some(blend);
+        self
+    }
+
+    pub fn with_shadows(mut self, casts_shadows: bool) -> Self {
+        self.casts_shadows = casts_shadows;
+        self
+    }
+
+    pub fn with_texture(mut self, name: &str, texture: Arc<Texture>) -> Self {
+        Arc::make_mut(&mut self.textures).insert(name.to_string(), texture);
+        self
+    }
+
+    pub fn with_materials(mut self, materials: Arc<Vec<Material>>) -> Self {
+        self.materials = materials;
+        self
+    }
+
+    pub fn blend_mode(&self) -> BlendMode {
+        self.blend.unwrap_or_else(|| self.shader.blend_mode())
+    }
+
+    // Cambia el shader y toma sus parámetros actuales como base. El shader declarado no
+    // cambia: sigue siendo el que recibió `new`.
+    pub fn set_shader(&mut self, shader: Box<dyn PlanetShader>) {
+        self.base_parameters = shader.parameters();
+        self.shader = shader;
+    }
+
+    pub fn declared_shader(&self) -> &str {
+        &self.declared_shader
+    }
+
+    // Parámetros con los que un archivo de parámetros empieza cada recarga
+    pub fn base_parameters(&self) -> &[ShaderParameter] {
+        &self.base_parameters
+    }
+
+    // Primero gira sobre su eje (Y) y luego se inclina el eje (Z)
+    pub fn model_matrix(&self) -> Mat4 {
+        let rotation = Vec3::new(
+            self.rotation.x,
+            self.rotation.y + self.spin_angle,
+            self.rotation.z + self.axial_tilt,
+        );
+        create_model_matrix(self.position, self.scale, rotation)
+    }
+}
+
+// Luz de la escena. Si `body` está definido, la luz puntual se mueve con ese cuerpo
+// (por ejemplo, la luz del sol).
+#[derive(Debug, Clone, Copy)]
+pub struct SceneLight {
+    pub light: Light,
+    pub body: Option<usize>,
+}
+
+// Un cuerpo o una luz que apunta a un cuerpo que todavía no está en la escena
+#[derive(Debug, Clone, PartialEq)]
+pub enum HierarchyError {
+    OrbitParent { body: String, parent: usize },
+    LightBody { body: usize },
+}
+
+impl fmt::Display for HierarchyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HierarchyError::OrbitParent { body, parent } => {
+                write!(f, "body '{}' orbits {}, which has not been added yet", body, parent)
+            }
+            HierarchyError::LightBody { body } => {
+                write!(f, "light attached to body {}, which has not been added yet", body)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HierarchyError {}
+
+// Sin luces, el renderer usa las que ya tiene en sus uniforms; sin cielo, el fondo es el
+// color de fondo del framebuffer
+#[derive(Default)]
+pub struct Scene {
+    pub bodies: Vec<Body>,
+    pub lights: Vec<SceneLight>,
+    pub sky: Option<Box<dyn Skybox>>,
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Scene { bodies: Vec::new(), lights: Vec::new(), sky: None }
+    }
+
+    // Los padres deben agregarse antes que sus satélites
+    pub fn add(&mut self, body: Body) -> Result<usize, HierarchyError> {
+        let index = self.bodies.len();
+        if let Some(orbit) = &body.orbit {
+            if orbit.parent >= index {
+                return Err(HierarchyError::OrbitParent { body: body.name, parent: orbit.parent });
+            }
+        }
+        self.bodies.push(body);
+        Ok(index)
+    }
+
+    pub fn add_light(&mut self, light: Light) {
+        self.lights.push(SceneLight { light, body: None });
+    }
+
+    // Luz puntual que sigue la posición del cuerpo `body`
+    pub fn add_light_on(&mut self, body: usize, light: Light) -> Result<(), HierarchyError> {
+        if body >= self.bodies.len() {
+            return Err(HierarchyError::LightBody { body });
+        }
+        let mut light = light;
+        light.kind = LightKind::Point { position: self.bodies[body].position };
+        self.lights.push(SceneLight { light, body: Some(body) });
+        Ok(())
+    }
+
+    // Luces en sus posiciones actuales
+    pub fn lights(&self) -> Vec<Light> {
+        self.lights.iter().map(|scene_light| scene_light.light).collect()
+    }
+
+    // Avanza órbitas y rotaciones. Como cada padre está antes que sus satélites, basta
+    // un recorrido en orden para que las lunas sigan a su planeta.
+    pub fn update(&mut self, time: u32) {
+        let time = time as f32;
+        for index in 0..self.bodies.len() {
+            if let Some(orbit) = self.bodies[index].orbit {
+                let parent_position = self.bodies[orbit.parent].position;
+                self.bodies[index].position = parent_position + orbit.position(time);
+            }
+
+            let body = &mut self.bodies[index];
+            if body.spin_period != 0.0 {
+                body.spin_angle = (2.0 * PI * time / body.spin_period).rem_euclid(2.0 * PI);
+            }
+        }
+
+        for scene_light in &mut self.lights {
+            if let Some(body) = scene_light.body {
+                scene_light.light.kind = LightKind::Point { position: self.bodies[body].position };
+            }
+        }
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Body> {
+        self.bodies.iter().find(|body| body.name == name)
+    }
+
+    pub fn find_mut(&mut self, name: &str) -> Option<&mut Body> {
+        self.bodies.iter_mut().find(|body| body.name == name)
+    }
+}
@@ -0,0 +1,684 @@
+// scene_file.rs
+//
+// Escenas descritas en TOML. Cada [[body]] declara su malla (obligatoria), shader,
+// parámetros del shader, ruido, transformación y órbita. Los ángulos se escriben en grados. Cada [[light]] es una
+// luz puntual (en `position` o sobre un cuerpo con `body`) o direccional (`direction`).
+// Las texturas de un cuerpo van en `textures = { surface = "mapa.png" }`. La tabla [sky]
+// opcional agrega el cielo de estrellas, con [sky.nebula] para la nebulosa, o un cielo de
+// imagen con `image` (equirectangular) o `cube` (seis caras +X, -X, +Y, -Y, +Z, -Z).
+// Las rutas relativas de mallas, texturas e imágenes parten siempre de la carpeta del
+// archivo, nunca del directorio actual.
+//
+// Los archivos de parámetros ajustan los shaders de una escena ya cargada:
+//     [shader.rocky_planet]        # todos los cuerpos con ese shader
+//     base_color = [139, 69, 19]
+//     [body.terra]                 # un cuerpo en particular (se aplica después)
+//     crater_zoom = 12.0
+
+use nalgebra_glm::Vec3;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::blend::BlendMode;
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::culling::{CullMode, FrontFace};
+use crate::light::Light;
+use crate::material::Material;
+use crate::noise::NoiseSettings;
+use crate::obj::Obj;
+use crate::orbit::Orbit;
+use crate::planet_shader::{ParamValue, ParameterError, ShaderRegistry};
+use crate::scene::{Body, Scene};
+use crate::skybox::{ImageSky, NebulaSettings, ProceduralSky, SkyImage, Skybox, StarfieldSettings};
+use crate::texture::{CubeMap, Filter, Texture};
+use crate::vertex::Vertex;
+
+#[derive(Debug)]
+pub enum SceneError {
+    Io { path: PathBuf, source: io::Error },
+    Parse { path: PathBuf, message: String },
+    // Malla que no se pudo cargar fuera de un archivo de escena, como la del modo --shader
+    Mesh { path: PathBuf, source: tobj::LoadError },
+    Body { path: PathBuf, body: String, message: String },
+    // `light` empieza en 1, en el orden del archivo
+    Light { path: PathBuf, light: usize, message: String },
+    Sky { path: PathBuf, message: String },
+    // Un valor de un archivo de parámetros bien escrito que el shader rechaza
+    Parameter { path: PathBuf, section: ParameterSection, source: ParameterError },
+    // Una sección de un archivo de parámetros que no corresponde a ningún cuerpo
+    UnmatchedSection { path: PathBuf, section: ParameterSection },
+}
+
+// Sección [shader.*] o [body.*] de un archivo de parámetros
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParameterSection {
+    Shader(String),
+    Body(String),
+}
+
+impl fmt::Display for ParameterSection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParameterSection::Shader(shader) => write!(f, "shader '{}'", shader),
+            ParameterSection::Body(body) => write!(f, "body '{}'", body),
+        }
+    }
+}
+
+impl fmt::Display for SceneError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SceneError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            SceneError::Parse { path, message } => write!(f, "{}: {}", path.display(), message),
+            SceneError::Mesh { path, source } => write!(f, "{}: failed to load mesh: {}", path.display(), source),
+            SceneError::Body { path, body, message } => {
+                write!(f, "{}: body '{}': {}", path.display(), body, message)
+            }
+            SceneError::Light { path, light, message } => {
+                write!(f, "{}: light #{}: {}", path.display(), light, message)
+            }
+            SceneError::Sky { path, message } => write!(f, "{}: sky: {}", path.display(), message),
+            SceneError::Parameter { path, section, source } => {
+                write!(f, "{}: {}: {}", path.display(), section, source)
+            }
+            SceneError::UnmatchedSection { path, section } => {
+                let reason = match section {
+                    ParameterSection::Shader(_) => "no body in the scene uses this shader",
+                    ParameterSection::Body(_) => "no body with this name in the scene",
+                };
+                write!(f, "{}: {}: {}", path.display(), section, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SceneError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SceneError::Io { source, .. } => Some(source),
+            SceneError::Mesh { source, .. } => Some(source),
+            SceneError::Parameter { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SceneFile {
+    camera: Option<CameraEntry>,
+    #[serde(default, rename = "body")]
+    bodies: Vec<BodyEntry>,
+    #[serde(default, rename = "light")]
+    lights: Vec<LightEntry>,
+    sky: Option<SkyEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CameraEntry {
+    eye: [f32; 3],
+    #[serde(default)]
+    center: [f32; 3],
+    #[serde(default = "default_up")]
+    up: [f32; 3],
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BodyEntry {
+    name: String,
+    mesh: String,
+    shader: String,
+    #[serde(default)]
+    parameters: toml::Table,
+    noise: Option<toml::Value>,
+    #[serde(default)]
+    textures: HashMap<String, String>,
+    #[serde(default)]
+    position: [f32; 3],
+    #[serde(default = "default_scale")]
+    scale: f32,
+    #[serde(default)]
+    rotation: [f32; 3],
+    #[serde(default)]
+    axial_tilt: f32,
+    #[serde(default)]
+    spin_period: f32,
+    #[serde(default)]
+    cull: CullMode,
+    #[serde(default)]
+    front_face: FrontFace,
+    blend: Option<BlendMode>,
+    #[serde(default = "default_cast_shadows")]
+    cast_shadows: bool,
+    orbit: Option<OrbitEntry>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum LightType {
+    Point,
+    Directional,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LightEntry {
+    #[serde(rename = "type")]
+    kind: LightType,
+    position: Option<[f32; 3]>,
+    body: Option<String>,
+    direction: Option<[f32; 3]>,
+    color: Option<toml::Value>,
+    #[serde(default = "default_intensity")]
+    intensity: f32,
+}
+
+// Los campos que faltan toman los valores de StarfieldSettings y NebulaSettings. Con
+// `image` o `cube` el cielo es una imagen y solo se usan `intensity` y `filter`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SkyEntry {
+    image: Option<String>,
+    cube: Option<[String; 6]>,
+    intensity: Option<f32>,
+    filter: Option<Filter>,
+    seed: Option<u64>,
+    star_cells: Option<usize>,
+    star_density: Option<f32>,
+    star_brightness: Option<f32>,
+    faintest_magnitude: Option<f32>,
+    twinkle: Option<f32>,
+    nebula: Option<NebulaEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NebulaEntry {
+    noise: Option<toml::Value>,
+    colors: Option<[toml::Value; 2]>,
+    intensity: Option<f32>,
+    coverage: Option<f32>,
+    resolution: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct OrbitEntry {
+    parent: String,
+    semi_major_axis: f32,
+    #[serde(default)]
+    eccentricity: f32,
+    #[serde(default)]
+    inclination: f32,
+    period: f32,
+    #[serde(default)]
+    phase: f32,
+}
+
+fn default_scale() -> f32 {
+    1.0
+}
+
+fn default_cast_shadows() -> bool {
+    true
+}
+
+fn default_intensity() -> f32 {
+    1.0
+}
+
+fn default_up() -> [f32; 3] {
+    [0.0, 1.0, 0.0]
+}
+
+fn vec3(values: [f32; 3]) -> Vec3 {
+    Vec3::new(values[0], values[1], values[2])
+}
+
+fn degrees(values: [f32; 3]) -> Vec3 {
+    vec3(values.map(f32::to_radians))
+}
+
+// Resultado de cargar un archivo de escena
+pub struct LoadedScene {
+    pub scene: Scene,
+    pub camera: Option<Camera>,
+    // Problemas que no impiden cargar la escena, como un .mtl que falta
+    pub warnings: Vec<String>,
+}
+
+// Vértices de un OBJ con sus materiales
+type Mesh = (Arc<Vec<Vertex>>, Arc<Vec<Material>>);
+
+// Mallas y texturas ya cargadas, por ruta, para no leer dos veces el mismo archivo
+#[derive(Default)]
+struct Assets {
+    meshes: HashMap<PathBuf, Mesh>,
+    textures: HashMap<PathBuf, Arc<Texture>>,
+    warnings: Vec<String>,
+}
+
+pub fn load_scene<P: AsRef<Path>>(path: P, registry: &ShaderRegistry) -> Result<LoadedScene, SceneError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|source| SceneError::Io { path: path.to_path_buf(), source })?;
+    parse_scene(&source, path, registry)
+}
+
+// `path` se usa en los mensajes de error y como base de las rutas relativas
+pub fn parse_scene(source: &str, path: &Path, registry: &ShaderRegistry) -> Result<LoadedScene, SceneError> {
+    let file: SceneFile = toml::from_str(source).map_err(|error| SceneError::Parse {
+        path: path.to_path_buf(),
+        message: error.to_string().trim_end().to_string(),
+    })?;
+
+    let directory = path.parent().unwrap_or(Path::new(""));
+    let mut scene = Scene::new();
+    let mut assets = Assets::default();
+
+    for entry in file.bodies {
+        let body_error = |message: String| SceneError::Body {
+            path: path.to_path_buf(),
+            body: entry.name.clone(),
+            message,
+        };
+
+        if scene.find(&entry.name).is_some() {
+            return Err(body_error("a body with this name was already declared".to_string()));
+        }
+        let body = build_body(&entry, &scene, registry, directory, &mut assets).map_err(&body_error)?;
+        scene.add(body).map_err(|error| body_error(error.to_string()))?;
+    }
+
+    for (index, entry) in file.lights.iter().enumerate() {
+        let light_error = |message: String| SceneError::Light { path: path.to_path_buf(), light: index + 1, message };
+        add_light(entry, &mut scene).map_err(light_error)?;
+    }
+
+    if let Some(entry) = &file.sky {
+        let sky = build_sky(entry, directory).map_err(|message| SceneError::Sky { path: path.to_path_buf(), message })?;
+        scene.sky = Some(sky);
+    }
+
+    scene.update(0);
+
+    let camera = file.camera.map(|camera| Camera::new(vec3(camera.eye), vec3(camera.center), vec3(camera.up)));
+    Ok(LoadedScene { scene, camera, warnings: assets.warnings })
+}
+
+fn build_body(
+    entry: &BodyEntry,
+    scene: &Scene,
+    registry: &ShaderRegistry,
+    directory: &Path,
+    assets: &mut Assets,
+) -> Result<Body, String> {
+    let mut shader = registry.create(&entry.shader).ok_or_else(|| {
+        format!("unknown shader '{}' (available: {})", entry.shader, registry.names().join(", "))
+    })?;
+    for (name, value) in &entry.parameters {
+        let value = parse_parameter(name, value).map_err(|error| error.to_string())?;
+        shader.set_parameter(name, value).map_err(|error| error.to_string())?;
+    }
+
+    let noise = match &entry.noise {
+        Some(value) => parse_noise(value)?,
+        None => NoiseSettings::generic(),
+    };
+
+    if entry.scale <= 0.0 {
+        return Err(format!("scale must be greater than 0, got {}", entry.scale));
+    }
+
+    let mesh_path = directory.join(&entry.mesh);
+    let (mesh, materials) = match assets.meshes.get(&mesh_path) {
+        Some((mesh, materials)) => (Arc::clone(mesh), Arc::clone(materials)),
+        None => {
+            let obj = Obj::load(&mesh_path.to_string_lossy())
+                .map_err(|error| format!("failed to load mesh '{}': {}", mesh_path.display(), error))?;
+            assets.warnings.extend_from_slice(obj.warnings());
+            let loaded = (Arc::new(obj.get_vertex_array()), Arc::new(obj.materials().to_vec()));
+            assets.meshes.insert(mesh_path, loaded.clone());
+            loaded
+        }
+    };
+
+    let mut body = Body::new(&entry.name, mesh, shader, Arc::new(noise.build()))
+        .with_materials(materials)
+        .with_position(vec3(entry.position))
+        .with_scale(entry.scale)
+        .with_rotation(degrees(entry.rotation))
+        .with_spin(entry.axial_tilt.to_radians(), entry.spin_period)
+        .with_culling(entry.cull, entry.front_face)
+        .with_shadows(entry.cast_shadows);
+
+    if let Some(blend) = entry.blend {
+        body = body.with_blend(blend);
+    }
+    for (name, path) in &entry.textures {
+        let path = directory.join(path);
+        let texture = match assets.textures.get(&path) {
+            Some(texture) => Arc::clone(texture),
+            None => {
+                let texture = Arc::new(Texture::load(&path).map_err(|error| format!("failed to load texture '{}': {}", name, error))?);
+                assets.textures.insert(path, Arc::clone(&texture));
+                texture
+            }
+        };
+        body = body.with_texture(name, texture);
+    }
+    if let Some(orbit) = &entry.orbit {
+        body = body.with_orbit(build_orbit(orbit, scene)?);
+    }
+
+    Ok(body)
+}
+
+fn add_light(entry: &LightEntry, scene: &mut Scene) -> Result<(), String> {
+    let color = match &entry.color {
+        Some(value) => parse_parameter("color", value)
+            .and_then(|value| value.as_color("color"))
+            .map_err(|error| error.to_string())?,
+        None => Color::from_float(1.0, 1.0, 1.0),
+    };
+    if entry.intensity < 0.0 {
+        return Err(format!("intensity must not be negative, got {}", entry.intensity));
+    }
+
+    match entry.kind {
+        LightType::Point => {
+            if entry.direction.is_some() {
+                return Err("point lights have no direction".to_string());
+            }
+            match (&entry.body, entry.position) {
+                (Some(name), None) => {
+                    let body = scene
+                        .bodies
+                        .iter()
+                        .position(|body| body.name == *name)
+                        .ok_or_else(|| format!("no body named '{}' to attach the light to", name))?;
+                    scene
+                        .add_light_on(body, Light::point(Vec3::zeros(), color, entry.intensity))
+                        .map_err(|error| error.to_string())?;
+                }
+                (None, Some(position)) => scene.add_light(Light::point(vec3(position), color, entry.intensity)),
+                _ => return Err("point lights need either a position or a body".to_string()),
+            }
+        }
+        LightType::Directional => {
+            if entry.position.is_some() || entry.body.is_some() {
+                return Err("directional lights only take a direction".to_string());
+            }
+            let direction = entry.direction.map(vec3).ok_or("directional lights need a direction")?;
+            if direction.magnitude_squared() == 0.0 {
+                return Err("light direction must not be zero".to_string());
+            }
+            scene.add_light(Light::directional(direction, color, entry.intensity));
+        }
+    }
+
+    Ok(())
+}
+
+fn build_sky(entry: &SkyEntry, directory: &Path) -> Result<Box<dyn Skybox>, String> {
+    let image = match (&entry.image, &entry.cube) {
+        (Some(_), Some(_)) => return Err("use either image or cube, not both".to_string()),
+        (Some(path), None) => SkyImage::Equirectangular(Texture::load(directory.join(path)).map_err(|error| error.to_string())?),
+        (None, Some(paths)) => {
+            let paths = paths.each_ref().map(|path| directory.join(path));
+            SkyImage::CubeMap(CubeMap::load(&paths).map_err(|error| error.to_string())?)
+        }
+        (None, None) => {
+            if entry.intensity.is_some() || entry.filter.is_some() {
+                return Err("intensity and filter only apply to image skies".to_string());
+            }
+            return Ok(Box::new(build_starfield(entry)?));
+        }
+    };
+
+    let procedural = [entry.star_density, entry.star_brightness, entry.faintest_magnitude, entry.twinkle];
+    if entry.seed.is_some() || entry.star_cells.is_some() || entry.nebula.is_some() || procedural.iter().any(Option::is_some) {
+        return Err("star and nebula settings only apply to procedural skies".to_string());
+    }
+    let mut sky = ImageSky::new(image);
+    sky.intensity = entry.intensity.unwrap_or(1.0);
+    if sky.intensity < 0.0 {
+        return Err(format!("intensity must not be negative, got {}", sky.intensity));
+    }
+    sky.sampler.filter = entry.filter.unwrap_or_default();
+    Ok(Box::new(sky))
+}
+
+fn build_starfield(entry: &SkyEntry) -> Result<ProceduralSky, String> {
+    let defaults = StarfieldSettings::default();
+    let stars = StarfieldSettings {
+        seed: entry.seed.unwrap_or(defaults.seed),
+        cells: entry.star_cells.unwrap_or(defaults.cells),
+        density: entry.star_density.unwrap_or(defaults.density),
+        brightness: entry.star_brightness.unwrap_or(defaults.brightness),
+        faintest_magnitude: entry.faintest_magnitude.unwrap_or(defaults.faintest_magnitude),
+        twinkle: entry.twinkle.unwrap_or(defaults.twinkle),
+    };
+    if stars.cells == 0 {
+        return Err("star_cells must be at least 1".to_string());
+    }
+    if !(0.0..=1.0).contains(&stars.density) {
+        return Err(format!("star_density must be between 0 and 1, got {}", stars.density));
+    }
+    if stars.brightness < 0.0 {
+        return Err(format!("star_brightness must not be negative, got {}", stars.brightness));
+    }
+    if !(0.0..=1.0).contains(&stars.twinkle) {
+        return Err(format!("twinkle must be between 0 and 1, got {}", stars.twinkle));
+    }
+
+    let nebula = entry.nebula.as_ref().map(build_nebula).transpose()?;
+    Ok(ProceduralSky::new(stars, nebula.as_ref()))
+}
+
+fn build_nebula(entry: &NebulaEntry) -> Result<NebulaSettings, String> {
+    let defaults = NebulaSettings::default();
+    let colors = match &entry.colors {
+        Some([first, second]) => {
+            let color = |value| {
+                parse_parameter("colors", value).and_then(|value| value.as_color("colors")).map_err(|error| error.to_string())
+            };
+            [color(first)?, color(second)?]
+        }
+        None => defaults.colors,
+    };
+    let nebula = NebulaSettings {
+        noise: entry.noise.as_ref().map(parse_noise).transpose()?.unwrap_or(defaults.noise),
+        colors,
+        intensity: entry.intensity.unwrap_or(defaults.intensity),
+        coverage: entry.coverage.unwrap_or(defaults.coverage),
+        resolution: entry.resolution.unwrap_or(defaults.resolution),
+    };
+    if nebula.intensity < 0.0 {
+        return Err(format!("nebula intensity must not be negative, got {}", nebula.intensity));
+    }
+    if !(0.0..=1.0).contains(&nebula.coverage) {
+        return Err(format!("nebula coverage must be between 0 and 1, got {}", nebula.coverage));
+    }
+    if nebula.resolution == 0 {
+        return Err("nebula resolution must be at least 1".to_string());
+    }
+    Ok(nebula)
+}
+
+fn build_orbit(entry: &OrbitEntry, scene: &Scene) -> Result<Orbit, String> {
+    let parent = scene
+        .bodies
+        .iter()
+        .position(|body| body.name == entry.parent)
+        .ok_or_else(|| format!("orbit parent '{}' must be declared before this body", entry.parent))?;
+
+    if !(0.0..1.0).contains(&entry.eccentricity) {
+        return Err(format!("orbit eccentricity must be in [0, 1), got {}", entry.eccentricity));
+    }
+    // 0 deja el cuerpo sobre su padre (capas de nubes o atmósferas)
+    if entry.semi_major_axis < 0.0 {
+        return Err(format!("orbit semi_major_axis must not be negative, got {}", entry.semi_major_axis));
+    }
+
+    Ok(Orbit::circular(parent, entry.semi_major_axis, entry.period)
+        .with_eccentricity(entry.eccentricity)
+        .with_inclination(entry.inclination.to_radians())
+        .with_phase(entry.phase.to_radians()))
+}
+
+// Un ruido puede ser el nombre de una configuración predefinida o una tabla completa
+fn parse_noise(value: &toml::Value) -> Result<NoiseSettings, String> {
+    match value {
+        toml::Value::String(name) => NoiseSettings::preset(name).ok_or_else(|| {
+            format!("unknown noise preset '{}' (available: {})", name, NoiseSettings::preset_names().join(", "))
+        }),
+        toml::Value::Table(_) => value
+            .clone()
+            .try_into()
+            .map_err(|error: toml::de::Error| format!("invalid noise: {}", error.message())),
+        _ => Err("noise must be a preset name or a table".to_string()),
+    }
+}
+
+// Números -> float; [r, g, b] o [r, g, b, a] (0-255), "#RRGGBB" o "#RRGGBBAA" -> color
+pub fn parse_parameter(name: &str, value: &toml::Value) -> Result<ParamValue, ParameterError> {
+    let invalid = |message: String| ParameterError::Invalid { name: name.to_string(), message };
+    match value {
+        toml::Value::Float(value) => Ok(ParamValue::Float(*value as f32)),
+        toml::Value::Integer(value) => Ok(ParamValue::Float(*value as f32)),
+        toml::Value::Array(components) => {
+            let channels: Vec<u8> = components
+                .iter()
+                .filter_map(|component| component.as_integer())
+                .filter_map(|component| u8::try_from(component).ok())
+                .collect();
+            match channels[..] {
+                [r, g, b] if components.len() == 3 => Ok(ParamValue::Color(Color::new(r, g, b))),
+                [r, g, b, a] if components.len() == 4 => Ok(ParamValue::Color(Color::new_rgba(r, g, b, a))),
+                _ => Err(invalid("colors must be [r, g, b] or [r, g, b, a] with values from 0 to 255".to_string())),
+            }
+        }
+        toml::Value::String(hex) => {
+            let digits = hex.strip_prefix('#').unwrap_or(hex);
+            match u32::from_str_radix(digits, 16) {
+                Ok(value) if digits.len() == 6 => Ok(ParamValue::Color(Color::from_hex(value))),
+                Ok(value) if digits.len() == 8 => Ok(ParamValue::Color(Color::from_hex_rgba(value))),
+                _ => Err(invalid(format!("'{}' is not a #RRGGBB or #RRGGBBAA color", hex))),
+            }
+        }
+        _ => Err(invalid("must be a number or a color".to_string())),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ParameterFileEntries {
+    #[serde(default)]
+    shader: HashMap<String, toml::Table>,
+    #[serde(default)]
+    body: HashMap<String, toml::Table>,
+}
+
+// Sección de la que sale cada valor, para que un error señale la que escribió el usuario.
+// Los parámetros base de la escena cuentan como del cuerpo.
+#[derive(Clone, Copy)]
+enum ParameterSource<'a> {
+    Shader(&'a str),
+    Body,
+}
+
+// Parámetros de shaders leídos de un archivo, listos para aplicarse sobre una escena
+pub struct ParameterFile {
+    path: PathBuf,
+    entries: ParameterFileEntries,
+}
+
+pub fn load_parameters<P: AsRef<Path>>(path: P) -> Result<ParameterFile, SceneError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|source| SceneError::Io { path: path.to_path_buf(), source })?;
+    parse_parameters(&source, path)
+}
+
+pub fn parse_parameters(source: &str, path: &Path) -> Result<ParameterFile, SceneError> {
+    let entries = toml::from_str(source).map_err(|error: toml::de::Error| SceneError::Parse {
+        path: path.to_path_buf(),
+        message: error.to_string().trim_end().to_string(),
+    })?;
+    Ok(ParameterFile { path: path.to_path_buf(), entries })
+}
+
+impl ParameterFile {
+    // Cada cuerpo vuelve a sus parámetros base (los valores por defecto del shader con los
+    // del archivo de escena) y encima van los parámetros por shader y luego los de cada
+    // cuerpo; una clave borrada del archivo recupera así su valor anterior. Se valida todo
+    // antes de tocar la escena, así un error no la deja a medio actualizar.
+    pub fn apply(&self, scene: &mut Scene) -> Result<(), SceneError> {
+        let mut updates: Vec<(usize, &str, ParamValue, ParameterSource)> = Vec::new();
+        for (index, body) in scene.bodies.iter().enumerate() {
+            updates.extend(body.base_parameters().iter().map(|parameter| (index, parameter.name, parameter.value, ParameterSource::Body)));
+        }
+
+        for (shader, parameters) in &self.entries.shader {
+            // El archivo se escribe para la escena, no para los shaders elegidos con C: una
+            // sección es un error solo si ningún cuerpo declara ni usa ese shader
+            if !scene.bodies.iter().any(|body| body.declared_shader() == shader || body.shader.name() == shader) {
+                return Err(self.unmatched_section(ParameterSection::Shader(shader.clone())));
+            }
+            let bodies: Vec<usize> = (0..scene.bodies.len()).filter(|&index| scene.bodies[index].shader.name() == shader).collect();
+            for (name, value) in parameters {
+                let value = parse_parameter(name, value).map_err(|error| self.shader_error(shader, error))?;
+                updates.extend(bodies.iter().map(|&index| (index, name.as_str(), value, ParameterSource::Shader(shader))));
+            }
+        }
+
+        for (body_name, parameters) in &self.entries.body {
+            let index = scene
+                .bodies
+                .iter()
+                .position(|body| body.name == *body_name)
+                .ok_or_else(|| self.unmatched_section(ParameterSection::Body(body_name.clone())))?;
+            // Con otro shader elegido con C los parámetros del cuerpo ya no corresponden
+            if scene.bodies[index].shader.name() != scene.bodies[index].declared_shader() {
+                continue;
+            }
+            for (name, value) in parameters {
+                let value = parse_parameter(name, value).map_err(|error| self.body_error(body_name, error))?;
+                updates.push((index, name.as_str(), value, ParameterSource::Body));
+            }
+        }
+
+        for &(index, name, value, source) in &updates {
+            let body = &scene.bodies[index];
+            body.shader.validate_parameter(name, value).map_err(|error| match source {
+                ParameterSource::Shader(shader) => self.shader_error(shader, error),
+                ParameterSource::Body => self.body_error(&body.name, error),
+            })?;
+        }
+
+        for (index, name, value, _) in updates {
+            scene.bodies[index].shader.set_parameter(name, value).expect("validate_parameter accepted this value");
+        }
+
+        Ok(())
+    }
+
+    fn shader_error(&self, shader: &str, source: ParameterError) -> SceneError {
+        SceneError::Parameter { path: self.path.clone(), section: ParameterSection::Shader(shader.to_string()), source }
+    }
+
+    fn body_error(&self, body: &str, source: ParameterError) -> SceneError {
+        SceneError::Parameter { path: self.path.clone(), section: ParameterSection::Body(body.to_string()), source }
+    }
+
+    fn unmatched_section(&self, section: ParameterSection) -> SceneError {
+        SceneError::UnmatchedSection { path: self.path.clone(), section }
+    }
+}
@@ -2,13 +2,19 @@
 
 use nalgebra_glm::{Vec3, Vec4, Mat3, mat4_to_mat3};
 use crate::vertex::Vertex;
-use crate::Uniforms;
+use crate::renderer::Uniforms;
 use crate::fragment::Fragment;
+use crate::blend::BlendMode;
 use crate::color::Color;
+use crate::material::Material;
+use crate::texture::{equirectangular_uv, Filter, Sampler, WrapMode};
+use crate::planet_shader::{PlanetShader, ShaderRegistry, ShaderParameter, ParamValue, ParameterError};
 use rand::Rng;
 use rand::SeedableRng;
 use rand::rngs::StdRng;
 
+// La posición queda en clip space; el recorte, la división de perspectiva y el viewport
+// se aplican después en el pipeline (ver clipping.rs)
 pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
 	// Transform position
 	let position = Vec4::new(
@@ -17,21 +23,10 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
 		vertex.position.z,
 		1.0
 	);
-	let transformed = uniforms.projection_matrix * uniforms.view_matrix * uniforms.model_matrix * position;
-
-	// Perform perspective division
-	let w = transformed.w;
-	let ndc_position = Vec4::new(
-		transformed.x / w,
-		transformed.y / w,
-		transformed.z / w,
-		1.0
-	);
+	let world_position = uniforms.model_matrix * position;
+	let clip_position = uniforms.projection_matrix * uniforms.view_matrix * world_position;
 
-	// apply viewport matrix
-	let screen_position = uniforms.viewport_matrix * ndc_position;
-
-	// Transform normal
+	// Transform normal (queda en espacio de mundo)
 	let model_mat3 = mat4_to_mat3(&uniforms.model_matrix); 
 	let normal_matrix = model_mat3.transpose().try_inverse().unwrap_or(Mat3::identity());
 
@@ -43,290 +38,952 @@ pub fn vertex_shader(vertex: &Vertex, uniforms: &Uniforms) -> Vertex {
 		normal: vertex.normal,
 		tex_coords: vertex.tex_coords,
 		color: vertex.color,
-		transformed_position: Vec3::new(screen_position.x, screen_position.y, screen_position.z),
+		clip_position,
+		transformed_position: vertex.transformed_position,
 		transformed_normal,
+		world_position: world_position.xyz(),
+		varyings: vertex.varyings,
+		material: vertex.material,
 	}
 }
 
-pub fn fragment_shader(fragment: &Fragment, uniforms: &Uniforms, current_shader: u32) -> Color {
-
-	// Call the appropriate shader based on the current_shader value
-	match current_shader {
-		0 => lava_planet_shader(fragment, uniforms),
-		1 => gas_planet_color(fragment, uniforms),
-		2 => sun_shader(fragment, uniforms),
-		3 => rocky_planet_shader(fragment, uniforms),
-		4 => gas_giant_shader(fragment, uniforms),
-		5 => ice_planet_shader(fragment, uniforms),
-		6 => wave_shader(fragment, uniforms),
-		7 => moon_shader(fragment, uniforms),
-        8 => atmospheric_shader(fragment, uniforms),
-        9 => dynamic_surface_shader(fragment, uniforms),
-        _ => Color::new(0, 0, 0),
-	}
+pub fn register_builtin_shaders(registry: &mut ShaderRegistry) {
+	registry.register(|| Box::new(LavaPlanetShader::default()));
+	registry.register(|| Box::new(GasPlanetShader::default()));
+	registry.register(|| Box::new(SunShader::default()));
+	registry.register(|| Box::new(RockyPlanetShader::default()));
+	registry.register(|| Box::new(GasGiantShader::default()));
+	registry.register(|| Box::new(IcePlanetShader::default()));
+	registry.register(|| Box::new(WaveShader::default()));
+	registry.register(|| Box::new(MoonShader::default()));
+	registry.register(|| Box::new(AtmosphericShader::default()));
+	registry.register(|| Box::new(DynamicSurfaceShader::default()));
+	registry.register(|| Box::new(CloudShader::default()));
+	registry.register(|| Box::new(TexturedShader::default()));
+	registry.register(|| Box::new(MaterialShader::default()));
 }
 
-fn atmospheric_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let noise_value = uniforms.noise.get_noise_3d(
-        fragment.vertex_position.x * 5.0,
-        fragment.vertex_position.y * 5.0,
-        uniforms.time as f32 * 0.02,
-    );
-
-    let base_color = Color::new(70, 130, 180); // Azul para la atmósfera
-    let cloud_color = Color::new(255, 255, 255); // Blanco para nubes
-
-    let blend_factor = (noise_value + 1.0) / 2.0; // Escalar a rango [0, 1]
-    base_color.lerp(&cloud_color, blend_factor)
+pub struct AtmosphericShader {
+    pub base_color: Color,
+    pub cloud_color: Color,
+    pub zoom: f32,
+    // Luz del lado nocturno
+    pub ambient: f32,
 }
 
-
-fn dynamic_surface_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let noise_value = uniforms.noise.get_noise_3d(
-        fragment.vertex_position.x * 3.0,
-        fragment.vertex_position.z * 3.0,
-        uniforms.time as f32 * 0.01,
-    );
-
-    let land_color = Color::new(34, 139, 34); // Verde para tierra
-    let water_color = Color::new(30, 144, 255); // Azul para agua
-
-    let blend_factor = (noise_value + 1.0) / 2.0;
-    land_color.lerp(&water_color, blend_factor)
+impl Default for AtmosphericShader {
+    fn default() -> Self {
+        AtmosphericShader {
+            base_color: Color::new(70, 130, 180), // Azul para la atmósfera
+            cloud_color: Color::new(255, 255, 255), // Blanco para nubes
+            zoom: 5.0,
+            ambient: 0.05,
+        }
+    }
 }
 
-
-fn wave_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    // Posición del fragmento
-    let pos = fragment.vertex_position;
-    
-    // Configuración de la onda
-    let wave_speed = 0.3;
-    let wave_frequency = 10.0;
-    let wave_amplitude = 0.07;
-    let time = uniforms.time as f32 * wave_speed;
-
-    // Calcular el desplazamiento basado en el ruido y la onda
-    let distance = (pos.x.powi(2) + pos.y.powi(2)).sqrt();
-    let ripple = (wave_frequency * (distance - time)).sin() * wave_amplitude;
-
-    // Colores de las ondas
-    let base_color = Color::new(70, 130, 180); // Azul acero
-    let ripple_color = Color::new(173, 216, 230); // Azul claro
-
-    // Mezclar los colores basados en el valor de la onda
-    let color_factor = ripple.clamp(0.0, 1.0);
-    let final_color = base_color.lerp(&ripple_color, color_factor);
-
-    // Aplicar intensidad para simular iluminación
-    final_color * fragment.intensity
+impl PlanetShader for AtmosphericShader {
+    fn name(&self) -> &str {
+        "atmospheric"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let noise_value = uniforms.noise.get_noise_3d(
+            fragment.vertex_position.x * self.zoom,
+            fragment.vertex_position.y * self.zoom,
+            uniforms.time as f32 * 0.02,
+        );
+
+        let blend_factor = (noise_value + 1.0) / 2.0; // Escalar a rango [0, 1]
+        let surface = self.base_color.lerp(&self.cloud_color, blend_factor);
+        lit_surface(surface, fragment, uniforms, self.ambient)
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("base_color", self.base_color),
+            ShaderParameter::color("cloud_color", self.cloud_color),
+            ShaderParameter::float("zoom", self.zoom),
+            ShaderParameter::float("ambient", self.ambient),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "base_color" => self.base_color = value.as_color(name)?,
+            "cloud_color" => self.cloud_color = value.as_color(name)?,
+            "zoom" => self.zoom = value.as_float(name)?,
+            "ambient" => self.ambient = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
 }
 
-fn moon_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let zoom = 50.0;
-    let x = fragment.vertex_position.x;
-    let y = fragment.vertex_position.y;
-    let t = uniforms.time as f32 * 0.1;
-
-    // Añadimos un efecto pulsante a los cráteres
-    let pulsate = (t * 0.5).sin() * 0.05;
+pub struct DynamicSurfaceShader {
+    pub land_color: Color,
+    pub water_color: Color,
+    pub zoom: f32,
+    // Luz del lado nocturno
+    pub ambient: f32,
+}
 
-    // Ruido para la textura de la superficie
-    let surface_noise = uniforms.noise.get_noise_2d(x * zoom + t, y * zoom + t);
+impl Default for DynamicSurfaceShader {
+    fn default() -> Self {
+        DynamicSurfaceShader {
+            land_color: Color::new(34, 139, 34), // Verde para tierra
+            water_color: Color::new(30, 144, 255), // Azul para agua
+            zoom: 3.0,
+            ambient: 0.05,
+        }
+    }
+}
 
-    let gray_color = Color::new(200, 200, 200);
-    let bright_crater_color = Color::new(220, 220, 220); // Cráter más brillante
-    let dynamic_color = Color::new(250, 250, 250); // Toque dinámico brillante
+impl PlanetShader for DynamicSurfaceShader {
+    fn name(&self) -> &str {
+        "dynamic_surface"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let noise_value = uniforms.noise.get_noise_3d(
+            fragment.vertex_position.x * self.zoom,
+            fragment.vertex_position.z * self.zoom,
+            uniforms.time as f32 * 0.01,
+        );
+
+        let blend_factor = (noise_value + 1.0) / 2.0;
+        let surface = self.land_color.lerp(&self.water_color, blend_factor);
+        lit_surface(surface, fragment, uniforms, self.ambient)
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("land_color", self.land_color),
+            ShaderParameter::color("water_color", self.water_color),
+            ShaderParameter::float("zoom", self.zoom),
+            ShaderParameter::float("ambient", self.ambient),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "land_color" => self.land_color = value.as_color(name)?,
+            "water_color" => self.water_color = value.as_color(name)?,
+            "zoom" => self.zoom = value.as_float(name)?,
+            "ambient" => self.ambient = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-    let crater_threshold = 0.4 + pulsate; // Dinamismo en los cráteres
+pub struct WaveShader {
+    pub base_color: Color,
+    pub ripple_color: Color,
+    pub wave_speed: f32,
+    pub wave_frequency: f32,
+    pub wave_amplitude: f32,
+    // Brillo especular del agua (Blinn-Phong)
+    pub shininess: f32,
+    pub specular: f32,
+}
 
-    // Definir el color base de la luna
-    let base_color = if surface_noise > crater_threshold {
-        gray_color
-    } else if surface_noise > crater_threshold - 0.1 {
-        bright_crater_color
-    } else {
-        dynamic_color // Zonas más dinámicas
-    };
+impl Default for WaveShader {
+    fn default() -> Self {
+        WaveShader {
+            base_color: Color::new(70, 130, 180), // Azul acero
+            ripple_color: Color::new(173, 216, 230), // Azul claro
+            wave_speed: 0.3,
+            wave_frequency: 10.0,
+            wave_amplitude: 0.07,
+            shininess: 32.0,
+            specular: 0.4,
+        }
+    }
+}
 
-    base_color * fragment.intensity
+impl PlanetShader for WaveShader {
+    fn name(&self) -> &str {
+        "wave"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        // Posición del fragmento
+        let pos = fragment.vertex_position;
+        let time = uniforms.time as f32 * self.wave_speed;
+
+        // Calcular el desplazamiento basado en el ruido y la onda
+        let distance = (pos.x.powi(2) + pos.y.powi(2)).sqrt();
+        let ripple = (self.wave_frequency * (distance - time)).sin() * self.wave_amplitude;
+
+        // Mezclar los colores basados en el valor de la onda
+        let color_factor = ripple.clamp(0.0, 1.0);
+        let final_color = self.base_color.lerp(&self.ripple_color, color_factor);
+
+        // Luz difusa con el color de las luces y el reflejo del sol sobre el agua
+        let lit = final_color.blend_multiply(&uniforms.diffuse(fragment));
+        lit + uniforms.specular(fragment, self.shininess) * self.specular
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("base_color", self.base_color),
+            ShaderParameter::color("ripple_color", self.ripple_color),
+            ShaderParameter::float("wave_speed", self.wave_speed),
+            ShaderParameter::float("wave_frequency", self.wave_frequency),
+            ShaderParameter::float("wave_amplitude", self.wave_amplitude),
+            ShaderParameter::float("shininess", self.shininess),
+            ShaderParameter::float("specular", self.specular),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "base_color" => self.base_color = value.as_color(name)?,
+            "ripple_color" => self.ripple_color = value.as_color(name)?,
+            "wave_speed" => self.wave_speed = value.as_float(name)?,
+            "wave_frequency" => self.wave_frequency = value.as_float(name)?,
+            "wave_amplitude" => self.wave_amplitude = value.as_float(name)?,
+            "shininess" => self.shininess = value.as_float(name)?,
+            "specular" => self.specular = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
 }
 
-fn gas_planet_color(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    // Utiliza la posición del fragmento y el tiempo para generar un "seed" para el ruido.
-    let seed = uniforms.time as f32 * fragment.vertex_position.y * fragment.vertex_position.x;
-    
-    // Crea un generador de números aleatorios basado en el seed.
-    let mut rng = StdRng::seed_from_u64(seed.abs() as u64);
-    
-    // Genera un número aleatorio para la variación en el color.
-    let random_number = rng.gen_range(0..=100);
+pub struct MoonShader {
+    pub zoom: f32,
+    pub gray_color: Color,
+    pub bright_crater_color: Color,
+    pub dynamic_color: Color,
+    pub crater_threshold: f32,
+}
 
-    // Define colores base para el planeta gaseoso.
-    let base_color = Color::new(70, 130, 180); // Azul
-    let cloud_color = Color::new(255, 255, 255); // Blanco para nubes
-    let shadow_color = Color::new(50, 50, 100); // Color oscuro para sombras
+impl Default for MoonShader {
+    fn default() -> Self {
+        MoonShader {
+            zoom: 50.0,
+            gray_color: Color::new(200, 200, 200),
+            bright_crater_color: Color::new(220, 220, 220), // Cráter más brillante
+            dynamic_color: Color::new(250, 250, 250), // Toque dinámico brillante
+            crater_threshold: 0.4,
+        }
+    }
+}
 
-    // Calcular el factor de nubes usando el ruido
-    let noise_value = uniforms.noise.get_noise_2d(fragment.vertex_position.x * 5.0, fragment.vertex_position.z * 5.0);
-    let cloud_factor = (noise_value * 0.5 + 0.5).powi(2); // Escala el ruido entre 0 y 1.
+impl PlanetShader for MoonShader {
+    fn name(&self) -> &str {
+        "moon"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let x = fragment.vertex_position.x;
+        let y = fragment.vertex_position.y;
+        let t = uniforms.time as f32 * 0.1;
+
+        // Añadimos un efecto pulsante a los cráteres
+        let pulsate = (t * 0.5).sin() * 0.05;
+
+        // Ruido para la textura de la superficie
+        let surface_noise = uniforms.noise.get_noise_2d(x * self.zoom + t, y * self.zoom + t);
+
+        let crater_threshold = self.crater_threshold + pulsate; // Dinamismo en los cráteres
+
+        // Definir el color base de la luna
+        let base_color = if surface_noise > crater_threshold {
+            self.gray_color
+        } else if surface_noise > crater_threshold - 0.1 {
+            self.bright_crater_color
+        } else {
+            self.dynamic_color // Zonas más dinámicas
+        };
+
+        base_color * fragment.intensity
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::float("zoom", self.zoom),
+            ShaderParameter::color("gray_color", self.gray_color),
+            ShaderParameter::color("bright_crater_color", self.bright_crater_color),
+            ShaderParameter::color("dynamic_color", self.dynamic_color),
+            ShaderParameter::float("crater_threshold", self.crater_threshold),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "zoom" => self.zoom = value.as_float(name)?,
+            "gray_color" => self.gray_color = value.as_color(name)?,
+            "bright_crater_color" => self.bright_crater_color = value.as_color(name)?,
+            "dynamic_color" => self.dynamic_color = value.as_color(name)?,
+            "crater_threshold" => self.crater_threshold = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-    // Selección de color basado en el número aleatorio para agregar variación.
-    let planet_color = if random_number < 50 {
-        base_color * (1.0 - cloud_factor) + cloud_color * cloud_factor
-    } else {
-        cloud_color * cloud_factor // Predominan las nubes
-    };
+pub struct GasPlanetShader {
+    pub base_color: Color,
+    pub cloud_color: Color,
+    pub shadow_color: Color,
+    pub glow_color: Color,
+    // Luz del lado nocturno
+    pub ambient: f32,
+}
 
-    // Añadir sombras sutiles
-    let shadow_factor = (1.0 - noise_value).max(0.0);
-    let shadow_effect = shadow_color * shadow_factor * 0.3; // Sombra suave
-
-    // Combina el color del planeta y las sombras
-    let final_color = planet_color + shadow_effect;
+impl Default for GasPlanetShader {
+    fn default() -> Self {
+        GasPlanetShader {
+            base_color: Color::new(70, 130, 180), // Azul
+            cloud_color: Color::new(255, 255, 255), // Blanco para nubes
+            shadow_color: Color::new(50, 50, 100), // Color oscuro para sombras
+            glow_color: Color::new(200, 200, 255), // Brillo azul claro
+            ambient: 0.05,
+        }
+    }
+}
 
-    // Brillo atmosférico (opcional)
-    let glow_color = Color::new(200, 200, 255); // Brillo azul claro
-    let glow_factor = (1.0 - (fragment.vertex_position.y / 10.0).max(0.0).min(1.0)).max(0.0); // Basado en altura
-    let final_glow = glow_color * glow_factor * 0.1; // Brillo sutil
+impl PlanetShader for GasPlanetShader {
+    fn name(&self) -> &str {
+        "gas_planet"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        // Utiliza la posición del fragmento y el tiempo para generar un "seed" para el ruido.
+        let seed = uniforms.time as f32 * fragment.vertex_position.y * fragment.vertex_position.x;
+
+        // Crea un generador de números aleatorios basado en el seed.
+        let mut rng = StdRng::seed_from_u64(seed.abs() as u64);
+
+        // Genera un número aleatorio para la variación en el color.
+        let random_number = rng.gen_range(0..=100);
+
+        // Calcular el factor de nubes usando el ruido
+        let noise_value = uniforms.noise.get_noise_2d(fragment.vertex_position.x * 5.0, fragment.vertex_position.z * 5.0);
+        let cloud_factor = (noise_value * 0.5 + 0.5).powi(2); // Escala el ruido entre 0 y 1.
+
+        // Selección de color basado en el número aleatorio para agregar variación.
+        let planet_color = if random_number < 50 {
+            self.base_color * (1.0 - cloud_factor) + self.cloud_color * cloud_factor
+        } else {
+            self.cloud_color * cloud_factor // Predominan las nubes
+        };
+
+        // Añadir sombras sutiles
+        let shadow_factor = (1.0 - noise_value).max(0.0);
+        let shadow_effect = self.shadow_color * shadow_factor * 0.3; // Sombra suave
+
+        // Combina el color del planeta y las sombras, iluminado por las luces de la escena
+        let final_color = lit_surface(planet_color + shadow_effect, fragment, uniforms, self.ambient);
+
+        // Brillo atmosférico (opcional)
+        let glow_factor = (1.0 - (fragment.vertex_position.y / 10.0).clamp(0.0, 1.0)).max(0.0); // Basado en altura
+        let final_glow = self.glow_color * glow_factor * 0.1; // Brillo sutil
+
+        // Devuelve el color final combinado
+        final_color + final_glow
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("base_color", self.base_color),
+            ShaderParameter::color("cloud_color", self.cloud_color),
+            ShaderParameter::color("shadow_color", self.shadow_color),
+            ShaderParameter::color("glow_color", self.glow_color),
+            ShaderParameter::float("ambient", self.ambient),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "base_color" => self.base_color = value.as_color(name)?,
+            "cloud_color" => self.cloud_color = value.as_color(name)?,
+            "shadow_color" => self.shadow_color = value.as_color(name)?,
+            "glow_color" => self.glow_color = value.as_color(name)?,
+            "ambient" => self.ambient = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-    // Devuelve el color final combinado
-    final_color + final_glow
+pub struct LavaPlanetShader {
+	pub bright_color: Color,
+	pub dark_color: Color,
+	pub base_frequency: f32,
+	pub pulsate_amplitude: f32,
+	pub zoom: f32,
 }
 
+impl Default for LavaPlanetShader {
+	fn default() -> Self {
+		LavaPlanetShader {
+			bright_color: Color::new(255, 240, 0), // Bright orange (lava-like)
+			dark_color: Color::new(130, 20, 0),   // Darker red-orange
+			base_frequency: 0.2,
+			pulsate_amplitude: 0.5,
+			zoom: 1000.0, // Constant zoom factor
+		}
+	}
+}
 
-fn lava_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-	// Base colors for the lava effect
-	let bright_color = Color::new(255, 240, 0); // Bright orange (lava-like)
-	let dark_color = Color::new(130, 20, 0);   // Darker red-orange
-
-	// Get fragment position
-	let position = Vec3::new(
-		fragment.vertex_position.x,
-		fragment.vertex_position.y,
-		fragment.depth
-	);
+impl PlanetShader for LavaPlanetShader {
+	fn name(&self) -> &str {
+		"lava_planet"
+	}
 
-	// Base frequency and amplitude for the pulsating effect
-	let base_frequency = 0.2;
-	let pulsate_amplitude = 0.5;
-	let t = uniforms.time as f32 * 0.01;
+	fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+		// Posición en el espacio del objeto, así las manchas se quedan pegadas a la superficie
+		let position = fragment.vertex_position;
+
+		let t = uniforms.time as f32 * 0.01;
+
+		// Pulsate on the z-axis to change spot size
+		let pulsate = (t * self.base_frequency).sin() * self.pulsate_amplitude;
+
+		// Apply noise to coordinates with subtle pulsating on z-axis
+		let zoom = self.zoom;
+		let noise_value1 = uniforms.noise.get_noise_3d(
+			position.x * zoom,
+			position.y * zoom,
+			(position.z + pulsate) * zoom
+		);
+		let noise_value2 = uniforms.noise.get_noise_3d(
+			(position.x + 1000.0) * zoom,
+			(position.y + 1000.0) * zoom,
+			(position.z + 1000.0 + pulsate) * zoom
+		);
+		let noise_value = (noise_value1 + noise_value2) * 0.5;  // Averaging noise for smoother transitions
+
+		// Use lerp for color blending based on noise value
+		let color = self.dark_color.lerp(&self.bright_color, noise_value);
+
+		color * fragment.intensity
+	}
 
-	// Pulsate on the z-axis to change spot size
-	let pulsate = (t * base_frequency).sin() * pulsate_amplitude;
+	fn parameters(&self) -> Vec<ShaderParameter> {
+		vec![
+			ShaderParameter::color("bright_color", self.bright_color),
+			ShaderParameter::color("dark_color", self.dark_color),
+			ShaderParameter::float("base_frequency", self.base_frequency),
+			ShaderParameter::float("pulsate_amplitude", self.pulsate_amplitude),
+			ShaderParameter::float("zoom", self.zoom),
+		]
+	}
 
-	// Apply noise to coordinates with subtle pulsating on z-axis
-	let zoom = 1000.0; // Constant zoom factor
-	let noise_value1 = uniforms.noise.get_noise_3d(
-		position.x * zoom,
-		position.y * zoom,
-		(position.z + pulsate) * zoom
-	);
-	let noise_value2 = uniforms.noise.get_noise_3d(
-		(position.x + 1000.0) * zoom,
-		(position.y + 1000.0) * zoom,
-		(position.z + 1000.0 + pulsate) * zoom
-	);
-	let noise_value = (noise_value1 + noise_value2) * 0.5;  // Averaging noise for smoother transitions
+	fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+		match name {
+			"bright_color" => self.bright_color = value.as_color(name)?,
+			"dark_color" => self.dark_color = value.as_color(name)?,
+			"base_frequency" => self.base_frequency = value.as_float(name)?,
+			"pulsate_amplitude" => self.pulsate_amplitude = value.as_float(name)?,
+			"zoom" => self.zoom = value.as_float(name)?,
+			_ => return Err(unknown_parameter(self, name)),
+		}
+		Ok(())
+	}
+}
 
-	// Use lerp for color blending based on noise value
-	let color = dark_color.lerp(&bright_color, noise_value);
+pub struct SunShader {
+    pub core_color: Color,
+    pub edge_color: Color,
+    pub glow_color: Color,
+    pub turbulence_zoom: f32,
+}
 
-	color * fragment.intensity
+impl Default for SunShader {
+    fn default() -> Self {
+        SunShader {
+            core_color: Color::new(255, 204, 0), // Brillante amarillo
+            edge_color: Color::new(255, 69, 0),  // Naranja más oscuro
+            glow_color: Color::new(255, 255, 224),
+            turbulence_zoom: 10.0,
+        }
+    }
 }
 
-fn sun_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let position = fragment.vertex_position;
-    let distance = position.magnitude(); // Distancia del centro
+impl PlanetShader for SunShader {
+    fn name(&self) -> &str {
+        "sun"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let position = fragment.vertex_position;
+        let distance = position.magnitude(); // Distancia del centro
+
+        // Noise to create surface turbulence
+        let noise_value = uniforms.noise.get_noise_3d(
+            position.x * self.turbulence_zoom,
+            position.y * self.turbulence_zoom,
+            uniforms.time as f32 * 0.01,
+        );
+        let turbulence = noise_value.abs();
+
+        // Blend core and edge colors based on distance from center
+        let blend_factor = (distance - 0.2).clamp(0.0, 1.0);
+        let base_color = self.core_color.lerp(&self.edge_color, blend_factor);
+
+        // Add dynamic turbulence effect
+        let dynamic_color = base_color * (1.0 + turbulence * 0.3);
+
+        // Glow effect based on proximity to the center
+        let glow_factor = (1.0 - distance).clamp(0.0, 1.0).powi(2);
+        let glow_color = self.glow_color * glow_factor;
+
+        dynamic_color + glow_color
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("core_color", self.core_color),
+            ShaderParameter::color("edge_color", self.edge_color),
+            ShaderParameter::color("glow_color", self.glow_color),
+            ShaderParameter::float("turbulence_zoom", self.turbulence_zoom),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "core_color" => self.core_color = value.as_color(name)?,
+            "edge_color" => self.edge_color = value.as_color(name)?,
+            "glow_color" => self.glow_color = value.as_color(name)?,
+            "turbulence_zoom" => self.turbulence_zoom = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-    // Base colors for the star
-    let core_color = Color::new(255, 204, 0); // Brillante amarillo
-    let edge_color = Color::new(255, 69, 0);  // Naranja más oscuro
+pub struct RockyPlanetShader {
+    pub base_color: Color,
+    pub crater_color: Color,
+    pub crater_zoom: f32,
+}
 
-    // Noise to create surface turbulence
-    let noise_value = uniforms.noise.get_noise_3d(position.x * 10.0, position.y * 10.0, uniforms.time as f32 * 0.01);
-    let turbulence = noise_value.abs();
+impl Default for RockyPlanetShader {
+    fn default() -> Self {
+        RockyPlanetShader {
+            base_color: Color::new(139, 69, 19),   // Marrón
+            crater_color: Color::new(105, 105, 105), // Gris oscuro
+            crater_zoom: 10.0,
+        }
+    }
+}
 
-    // Blend core and edge colors based on distance from center
-    let blend_factor = (distance - 0.2).clamp(0.0, 1.0);
-    let base_color = core_color.lerp(&edge_color, blend_factor);
+impl PlanetShader for RockyPlanetShader {
+    fn name(&self) -> &str {
+        "rocky_planet"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let position = fragment.vertex_position * self.crater_zoom;
+
+        // Generate noise for surface texture
+        let crater_noise = uniforms.noise.get_noise_3d(position.x, position.y, position.z).abs();
+
+        // Simulate craters
+        let crater_factor = (crater_noise - 0.5).clamp(0.0, 1.0).powi(2); // Cráter más profundo al acercarse a 1.0
+
+        // Blend base color with crater color
+        let rocky_color = self.base_color.lerp(&self.crater_color, crater_factor);
+
+        // Simulate lighting intensity
+        rocky_color * fragment.intensity
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("base_color", self.base_color),
+            ShaderParameter::color("crater_color", self.crater_color),
+            ShaderParameter::float("crater_zoom", self.crater_zoom),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "base_color" => self.base_color = value.as_color(name)?,
+            "crater_color" => self.crater_color = value.as_color(name)?,
+            "crater_zoom" => self.crater_zoom = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-    // Add dynamic turbulence effect
-    let dynamic_color = base_color * (1.0 + turbulence * 0.3);
+pub struct GasGiantShader {
+    pub base_color: Color,
+    pub band_color: Color,
+    pub glow_color: Color,
+    pub band_frequency: f32,
+    // Luz del lado nocturno
+    pub ambient: f32,
+}
 
-    // Glow effect based on proximity to the center
-    let glow_factor = (1.0 - distance).clamp(0.0, 1.0).powi(2);
-    let glow_color = Color::new(255, 255, 224) * glow_factor;
+impl Default for GasGiantShader {
+    fn default() -> Self {
+        GasGiantShader {
+            base_color: Color::new(70, 130, 180), // Azul
+            band_color: Color::new(255, 255, 255), // Blanco para las bandas
+            glow_color: Color::new(200, 200, 255), // Azul claro
+            band_frequency: 10.0,
+            ambient: 0.05,
+        }
+    }
+}
 
-    dynamic_color + glow_color
+impl PlanetShader for GasGiantShader {
+    fn name(&self) -> &str {
+        "gas_giant"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let position = fragment.vertex_position;
+
+        // Generate horizontal bands using sine waves
+        let band_factor = (position.y * self.band_frequency).sin().abs();
+
+        // Turbulence effect
+        let turbulence = uniforms.noise.get_noise_3d(position.x * 5.0, position.y * 5.0, uniforms.time as f32 * 0.01).abs();
+
+        // Blend band and base colors
+        let gas_color = self.base_color.lerp(&self.band_color, band_factor * turbulence);
+
+        // Add slight glow to simulate atmospheric scattering
+        let glow_factor = (1.0 - position.magnitude() / 10.0).clamp(0.0, 1.0);
+        let final_glow = self.glow_color * glow_factor * 0.1;
+
+        lit_surface(gas_color, fragment, uniforms, self.ambient) + final_glow
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("base_color", self.base_color),
+            ShaderParameter::color("band_color", self.band_color),
+            ShaderParameter::color("glow_color", self.glow_color),
+            ShaderParameter::float("band_frequency", self.band_frequency),
+            ShaderParameter::float("ambient", self.ambient),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "base_color" => self.base_color = value.as_color(name)?,
+            "band_color" => self.band_color = value.as_color(name)?,
+            "glow_color" => self.glow_color = value.as_color(name)?,
+            "band_frequency" => self.band_frequency = value.as_float(name)?,
+            "ambient" => self.ambient = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
 }
 
-fn rocky_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let position = fragment.vertex_position;
+pub struct IcePlanetShader {
+	pub base_color: Color,
+	pub ice_color: Color,
+	pub glow_color: Color,
+	pub zoom: f32,
+	// Luz del lado nocturno
+	pub ambient: f32,
+}
 
-    // Base colors for rocky surface
-    let base_color = Color::new(139, 69, 19);   // Marrón
-    let crater_color = Color::new(105, 105, 105); // Gris oscuro
+impl Default for IcePlanetShader {
+	fn default() -> Self {
+		IcePlanetShader {
+			base_color: Color::new(240, 248, 255), // Blanco azulado
+			ice_color: Color::new(173, 216, 230),  // Azul claro
+			glow_color: Color::new(200, 200, 255), // Azul claro
+			zoom: 5.0,
+			ambient: 0.05,
+		}
+	}
+}
 
-    // Generate noise for surface texture
-    let _surface_noise = uniforms.noise.get_noise_3d(position.x * 5.0, position.y * 5.0, position.z * 5.0);
-    let crater_noise = uniforms.noise.get_noise_3d(position.x * 10.0, position.y * 10.0, position.z * 10.0).abs();
+impl PlanetShader for IcePlanetShader {
+	fn name(&self) -> &str {
+		"ice_planet"
+	}
 
-    // Simulate craters
-    let crater_factor = (crater_noise - 0.5).clamp(0.0, 1.0).powi(2); // Cráter más profundo al acercarse a 1.0
+	fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+		let position = fragment.vertex_position;
 
-    // Blend base color with crater color
-    let rocky_color = base_color.lerp(&crater_color, crater_factor);
+		// Generate noise for surface texture
+		let noise_value = uniforms.noise.get_noise_3d(position.x * self.zoom, position.y * self.zoom, position.z * self.zoom);
+		let ice_factor = (noise_value * 0.5 + 0.5).powi(2); // Escala el ruido entre 0 y 1.
 
-    // Simulate lighting intensity
-    rocky_color * fragment.intensity
-}
+		// Blend base color with ice color
+		let ice_planet_color = self.base_color.lerp(&self.ice_color, ice_factor);
 
+		// Add slight glow to simulate atmospheric scattering
+		let glow_factor = (1.0 - position.magnitude() / 10.0).clamp(0.0, 1.0);
+		let final_glow = self.glow_color * glow_factor * 0.1;
 
-fn gas_giant_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-    let position = fragment.vertex_position;
+		lit_surface(ice_planet_color, fragment, uniforms, self.ambient) + final_glow
+	}
 
-    // Base colors for gas giant bands
-    let base_color = Color::new(70, 130, 180); // Azul
-    let band_color = Color::new(255, 255, 255); // Blanco para las bandas
+	fn needs_intensity(&self) -> bool {
+		false
+	}
 
-    // Generate horizontal bands using sine waves
-    let band_factor = (position.y * 10.0).sin().abs();
+	fn parameters(&self) -> Vec<ShaderParameter> {
+		vec![
+			ShaderParameter::color("base_color", self.base_color),
+			ShaderParameter::color("ice_color", self.ice_color),
+			ShaderParameter::color("glow_color", self.glow_color),
+			ShaderParameter::float("zoom", self.zoom),
+			ShaderParameter::float("ambient", self.ambient),
+		]
+	}
 
-    // Turbulence effect
-    let turbulence = uniforms.noise.get_noise_3d(position.x * 5.0, position.y * 5.0, uniforms.time as f32 * 0.01).abs();
+	fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+		match name {
+			"base_color" => self.base_color = value.as_color(name)?,
+			"ice_color" => self.ice_color = value.as_color(name)?,
+			"glow_color" => self.glow_color = value.as_color(name)?,
+			"zoom" => self.zoom = value.as_float(name)?,
+			"ambient" => self.ambient = value.as_float(name)?,
+			_ => return Err(unknown_parameter(self, name)),
+		}
+		Ok(())
+	}
+}
 
-    // Blend band and base colors
-    let gas_color = base_color.lerp(&band_color, band_factor * turbulence);
+// Capa de nubes semitransparente para dibujar sobre otro planeta con una esfera un poco
+// más grande. La opacidad sale del ruido: `coverage` es la fracción del cielo cubierta.
+pub struct CloudShader {
+    pub cloud_color: Color,
+    pub coverage: f32,
+    pub opacity: f32,
+    pub zoom: f32,
+}
 
-    // Add slight glow to simulate atmospheric scattering
-    let glow_color = Color::new(200, 200, 255); // Azul claro
-    let glow_factor = (1.0 - position.magnitude() / 10.0).clamp(0.0, 1.0);
-    let final_glow = glow_color * glow_factor * 0.1;
+impl Default for CloudShader {
+    fn default() -> Self {
+        CloudShader {
+            cloud_color: Color::new(255, 255, 255),
+            coverage: 0.5,
+            opacity: 0.8,
+            zoom: 4.0,
+        }
+    }
+}
 
-    gas_color + final_glow
+impl PlanetShader for CloudShader {
+    fn name(&self) -> &str {
+        "clouds"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let position = fragment.vertex_position * self.zoom;
+        let noise_value = uniforms.noise.get_noise_3d(position.x, position.y + uniforms.time as f32 * 0.01, position.z);
+        let density = (noise_value + 1.0) / 2.0; // Escalar a rango [0, 1]
+
+        // Borde suave de 0.2 alrededor del umbral para que las nubes no queden recortadas
+        let alpha = ((density - (1.0 - self.coverage)) / 0.2).clamp(0.0, 1.0);
+        self.cloud_color.with_alpha(alpha * self.opacity)
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        BlendMode::Alpha
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("cloud_color", self.cloud_color),
+            ShaderParameter::float("coverage", self.coverage),
+            ShaderParameter::float("opacity", self.opacity),
+            ShaderParameter::float("zoom", self.zoom),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "cloud_color" => self.cloud_color = value.as_color(name)?,
+            "coverage" => self.coverage = value.as_float(name)?,
+            "opacity" => self.opacity = value.as_float(name)?,
+            "zoom" => self.zoom = value.as_float(name)?,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
 }
 
+// Planeta con la textura "surface" del cuerpo proyectada en equirectangular sobre la
+// esfera; el ruido le agrega detalle. Sin textura se dibuja un tablero de ajedrez. Se lee con
+// filtro trilineal y anisotrópico para que la textura no parpadee al alejarse.
+pub struct TexturedShader {
+    pub tint: Color,
+    pub detail: f32,
+    pub detail_zoom: f32,
+    pub sampler: Sampler,
+}
 
-fn ice_planet_shader(fragment: &Fragment, uniforms: &Uniforms) -> Color {
-	let position = fragment.vertex_position;
+impl Default for TexturedShader {
+    fn default() -> Self {
+        TexturedShader {
+            tint: Color::new(255, 255, 255),
+            detail: 0.25,
+            detail_zoom: 8.0,
+            sampler: Sampler::new(Filter::Trilinear, WrapMode::Repeat).with_anisotropy(4),
+        }
+    }
+}
 
-	// Base colors for the ice planet
-	let base_color = Color::new(240, 248, 255); // Blanco azulado
-	let ice_color = Color::new(173, 216, 230);  // Azul claro
+impl PlanetShader for TexturedShader {
+    fn name(&self) -> &str {
+        "textured"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        // Vista desde afuera: se invierte z para que la imagen no quede espejada
+        let outside = |p: &Vec3| Vec3::new(p.x, p.y, -p.z);
+        let p = fragment.vertex_position;
+        let direction = outside(&p);
+        let surface = match uniforms.texture("surface") {
+            Some(texture) => {
+                let (direction_dx, direction_dy) = (outside(&fragment.vertex_position_dx), outside(&fragment.vertex_position_dy));
+                texture.sample_equirectangular_grad(&direction, &direction_dx, &direction_dy, &self.sampler)
+            }
+            None => {
+                let uv = equirectangular_uv(&direction);
+                let checker = ((uv.x * 16.0).floor() + (uv.y * 8.0).floor()) as i32 % 2 == 0;
+                if checker { Color::new(220, 220, 220) } else { Color::new(60, 60, 60) }
+            }
+        };
+
+        let position = p * self.detail_zoom;
+        let noise_value = uniforms.noise.get_noise_3d(position.x, position.y, position.z);
+        let detail = 1.0 + noise_value * self.detail;
+        surface.blend_multiply(&self.tint) * (detail * fragment.intensity)
+    }
+
+    fn needs_derivatives(&self) -> bool {
+        true
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::color("tint", self.tint),
+            ShaderParameter::float("detail", self.detail),
+            ShaderParameter::float("detail_zoom", self.detail_zoom),
+            ShaderParameter::float("anisotropy", self.sampler.anisotropy as f32),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "tint" => self.tint = value.as_color(name)?,
+            "detail" => self.detail = value.as_float(name)?,
+            "detail_zoom" => self.detail_zoom = value.as_float(name)?,
+            "anisotropy" => self.sampler.anisotropy = value.as_float(name)?.round().max(0.0) as u32,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-	// Generate noise for surface texture
-	let noise_value = uniforms.noise.get_noise_3d(position.x * 5.0, position.y * 5.0, position.z * 5.0);
-	let ice_factor = (noise_value * 0.5 + 0.5).powi(2); // Escala el ruido entre 0 y 1.
+// Colores de los materiales del OBJ (.mtl): difuso con su mapa, especular con su
+// exponente, luz propia y opacidad. Las mallas sin material usan `Material::default`.
+// La opacidad solo se nota si el cuerpo se dibuja con `blend = "alpha"`.
+pub struct MaterialShader {
+    // Luz mínima en el lado oscuro, como fracción del color difuso
+    pub ambient: f32,
+    pub sampler: Sampler,
+    default_material: Material,
+}
 
-	// Blend base color with ice color
-	let ice_planet_color = base_color.lerp(&ice_color, ice_factor);
+impl Default for MaterialShader {
+    fn default() -> Self {
+        MaterialShader {
+            ambient: 0.05,
+            sampler: Sampler::new(Filter::Trilinear, WrapMode::Repeat).with_anisotropy(4),
+            default_material: Material::default(),
+        }
+    }
+}
 
-	// Add slight glow to simulate atmospheric scattering
-	let glow_color = Color::new(200, 200, 255); // Azul claro
-	let glow_factor = (1.0 - position.magnitude() / 10.0).clamp(0.0, 1.0);
-	let final_glow = glow_color * glow_factor * 0.1;
+impl PlanetShader for MaterialShader {
+    fn name(&self) -> &str {
+        "material"
+    }
+
+    fn fragment(&self, fragment: &Fragment, uniforms: &Uniforms) -> Color {
+        let material = uniforms.material(fragment.material).unwrap_or(&self.default_material);
+
+        let (mut diffuse, mut opacity) = (material.diffuse, material.opacity);
+        if let Some(map) = &material.diffuse_map {
+            let texel = map.sample_grad(fragment.tex_coords, fragment.tex_coords_dx, fragment.tex_coords_dy, &self.sampler);
+            diffuse = diffuse.blend_multiply(&texel);
+            opacity *= texel.a();
+        }
+
+        let lit = diffuse.blend_multiply(&uniforms.diffuse(fragment)) + diffuse * self.ambient;
+        let specular = material.specular.blend_multiply(&uniforms.specular(fragment, material.shininess));
+        (lit + specular + material.emissive).with_alpha(opacity)
+    }
+
+    fn needs_intensity(&self) -> bool {
+        false
+    }
+
+    fn needs_derivatives(&self) -> bool {
+        true
+    }
+
+    fn parameters(&self) -> Vec<ShaderParameter> {
+        vec![
+            ShaderParameter::float("ambient", self.ambient),
+            ShaderParameter::float("anisotropy", self.sampler.anisotropy as f32),
+        ]
+    }
+
+    fn set_parameter(&mut self, name: &str, value: ParamValue) -> Result<(), ParameterError> {
+        match name {
+            "ambient" => self.ambient = value.as_float(name)?,
+            "anisotropy" => self.sampler.anisotropy = value.as_float(name)?.round().max(0.0) as u32,
+            _ => return Err(unknown_parameter(self, name)),
+        }
+        Ok(())
+    }
+}
 
-	ice_planet_color + final_glow
+// Color de la superficie con la luz difusa (y el color) de las luces, más una fracción
+// `ambient` para que el lado nocturno no quede negro
+fn lit_surface(surface: Color, fragment: &Fragment, uniforms: &Uniforms, ambient: f32) -> Color {
+	surface.blend_multiply(&uniforms.diffuse(fragment)) + surface * ambient
 }
 
+fn unknown_parameter(shader: &dyn PlanetShader, name: &str) -> ParameterError {
+	ParameterError::Unknown { shader: shader.name().to_string(), name: name.to_string() }
+}
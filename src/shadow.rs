@@ -0,0 +1,221 @@
+// shadow.rs
+//
+// Sombras de luces puntuales con un cube map de profundidad. Para cada luz se renderizan
+// seis caras de 90° desde su posición guardando la distancia lineal a la luz del punto más
+// cercano; al sombrear, un punto está en sombra si está más lejos que lo guardado en la
+// dirección que lo une con la luz. El filtrado PCF promedia varios texels vecinos para
+// suavizar el borde de la sombra.
+
+use nalgebra_glm::{perspective, Mat4, Vec3, Vec4};
+use std::f32::consts::FRAC_PI_2;
+use std::thread;
+
+use crate::antialiasing::AntiAliasing;
+use crate::clipping::{clip_to_frustum, project_to_screen};
+use crate::cubemap::face_and_uv;
+use crate::culling::{is_culled, CullMode, FrontFace};
+use crate::fragment::Fragment;
+use crate::matrix::{create_view_matrix, create_viewport_matrix};
+use crate::triangle::{rasterize_in, FragmentSink, Interpolation, PixelRect};
+use crate::vertex::Vertex;
+
+// Ajustes de las sombras; los usa el renderer en cada draw_scene
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowSettings {
+    pub enabled: bool,
+    // Lado de cada cara del cube map, en texels
+    pub size: usize,
+    // Margen en unidades de mundo para que una superficie no se sombree a sí misma
+    pub bias: f32,
+    // Radio del filtro PCF en texels: 0 es una sola muestra, 1 un bloque de 3x3
+    pub pcf_radius: i32,
+    // Distancia máxima a la que la luz proyecta sombras
+    pub far: f32,
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        ShadowSettings { enabled: true, size: 512, bias: 0.05, pcf_radius: 1, far: 100.0 }
+    }
+}
+
+const NEAR: f32 = 0.05;
+
+// Dirección y vector "arriba" de cada cara: +X, -X, +Y, -Y, +Z, -Z
+const FACES: [([f32; 3], [f32; 3]); 6] = [
+    ([1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]),
+    ([0.0, 1.0, 0.0], [0.0, 0.0, 1.0]),
+    ([0.0, -1.0, 0.0], [0.0, 0.0, -1.0]),
+    ([0.0, 0.0, 1.0], [0.0, -1.0, 0.0]),
+    ([0.0, 0.0, -1.0], [0.0, -1.0, 0.0]),
+];
+
+// Una cara del cube map: matriz de vista y proyección y distancia a la luz por texel.
+// `vertices` guarda los vértices transformados de cada caster y se reutiliza entre frames.
+struct CubeFace {
+    view_projection: Mat4,
+    distances: Vec<f32>,
+    vertices: Vec<Vertex>,
+}
+
+// Un objeto que proyecta sombra: sus vértices, su matriz de modelo y su culling normal
+pub struct ShadowCaster<'a> {
+    pub mesh: &'a [Vertex],
+    pub model_matrix: Mat4,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+}
+
+pub struct CubeShadowMap {
+    light_position: Vec3,
+    size: usize,
+    viewport: Mat4,
+    faces: Vec<CubeFace>,
+}
+
+impl CubeShadowMap {
+    pub fn new(light_position: Vec3, settings: &ShadowSettings) -> Self {
+        let size = settings.size.max(1);
+        let projection = perspective(1.0, FRAC_PI_2, NEAR, settings.far);
+        let faces = FACES
+            .iter()
+            .map(|&(direction, up)| {
+                let view = create_view_matrix(light_position, light_position + Vec3::from(direction), Vec3::from(up));
+                CubeFace { view_projection: projection * view, distances: vec![f32::INFINITY; size * size], vertices: Vec::new() }
+            })
+            .collect();
+
+        CubeShadowMap {
+            light_position,
+            size,
+            viewport: create_viewport_matrix(size as f32, size as f32),
+            faces,
+        }
+    }
+
+    pub fn light_position(&self) -> Vec3 {
+        self.light_position
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // Distancia guardada en el texel (x, y) de la cara `face` (0..6, en el orden +X, -X, +Y, -Y, +Z, -Z)
+    pub fn distance(&self, face: usize, x: usize, y: usize) -> f32 {
+        self.faces[face].distances[y * self.size + x]
+    }
+
+    // Renderiza la distancia de los objetos a la luz en las seis caras, usando hasta `threads` hilos
+    pub fn render(&mut self, casters: &[ShadowCaster], threads: usize) {
+        let (size, viewport, light_position) = (self.size, self.viewport, self.light_position);
+        let draw_face = |face: &mut CubeFace| {
+            face.distances.fill(f32::INFINITY);
+            render_face(face, casters, size, &viewport, light_position);
+        };
+
+        if threads > 1 {
+            thread::scope(|scope| {
+                for face in &mut self.faces {
+                    scope.spawn(move || draw_face(face));
+                }
+            });
+        } else {
+            self.faces.iter_mut().for_each(draw_face);
+        }
+    }
+
+    // Fracción de luz que llega a `position` (1 = iluminado, 0 = en sombra), promediando
+    // un bloque de (2r + 1)² texels. El filtro no cruza a las caras vecinas.
+    pub fn visibility(&self, position: &Vec3, bias: f32, pcf_radius: i32) -> f32 {
+        let to_point = position - self.light_position;
+        let distance = to_point.magnitude();
+        if distance == 0.0 {
+            return 1.0;
+        }
+
+        let (face, _, _) = face_and_uv(&to_point);
+        let clip = self.faces[face].view_projection * Vec4::new(position.x, position.y, position.z, 1.0);
+        if clip.w <= 0.0 {
+            return 1.0;
+        }
+        let screen = self.viewport * Vec4::new(clip.x / clip.w, clip.y / clip.w, 0.0, 1.0);
+
+        let last = self.size as i32 - 1;
+        let (center_x, center_y) = (screen.x.floor() as i32, screen.y.floor() as i32);
+        let mut lit = 0;
+        let mut total = 0;
+        for dy in -pcf_radius..=pcf_radius {
+            for dx in -pcf_radius..=pcf_radius {
+                let x = (center_x + dx).clamp(0, last) as usize;
+                let y = (center_y + dy).clamp(0, last) as usize;
+                if distance - bias <= self.distance(face, x, y) {
+                    lit += 1;
+                }
+                total += 1;
+            }
+        }
+        lit as f32 / total as f32
+    }
+}
+
+// Guarda la distancia mínima a la luz de cada texel
+struct DistanceSink<'a> {
+    distances: &'a mut [f32],
+    size: usize,
+    light_position: Vec3,
+}
+
+impl FragmentSink for DistanceSink<'_> {
+    fn needs_derivatives(&self) -> bool {
+        false
+    }
+
+    fn emit(&mut self, fragment: Fragment) {
+        let index = fragment.position.y as usize * self.size + fragment.position.x as usize;
+        let distance = (fragment.world_position - self.light_position).magnitude();
+        if distance < self.distances[index] {
+            self.distances[index] = distance;
+        }
+    }
+}
+
+// Solo transforma posiciones (sin el vertex stage de los shaders). Se descartan las caras
+// que miran a la luz para guardar la cara trasera de los objetos cerrados y evitar que
+// se sombreen a sí mismos; los objetos sin culling se dibujan completos.
+fn render_face(face: &mut CubeFace, casters: &[ShadowCaster], size: usize, viewport: &Mat4, light_position: Vec3) {
+    let rect = PixelRect { min_x: 0, min_y: 0, max_x: size as i32 - 1, max_y: size as i32 - 1 };
+    let mut sink = DistanceSink { distances: &mut face.distances, size, light_position };
+    let vertices = &mut face.vertices;
+
+    for caster in casters {
+        let cull_mode = match caster.cull_mode {
+            CullMode::Back => CullMode::Front,
+            _ => CullMode::None,
+        };
+
+        vertices.clear();
+        vertices.extend(caster.mesh.iter().map(|vertex| {
+            let world = caster.model_matrix * Vec4::new(vertex.position.x, vertex.position.y, vertex.position.z, 1.0);
+            Vertex {
+                clip_position: face.view_projection * world,
+                world_position: world.xyz(),
+                ..vertex.clone()
+            }
+        }));
+
+        for tri in vertices.chunks_exact(3) {
+            for mut clipped in clip_to_frustum(&tri[0], &tri[1], &tri[2]) {
+                for vertex in &mut clipped {
+                    project_to_screen(vertex, viewport);
+                }
+                let [a, b, c] = [0, 1, 2].map(|i| clipped[i].transformed_position);
+                if is_culled(&a, &b, &c, cull_mode, caster.front_face) {
+                    continue;
+                }
+                rasterize_in(&clipped[0], &clipped[1], &clipped[2], Interpolation::Perspective, AntiAliasing::None, &rect, &mut sink);
+            }
+        }
+    }
+}
@@ -0,0 +1,341 @@
+// skybox.rs
+//
+// Fondo del cielo. Un `Skybox` da el color HDR (lineal) que se ve en una dirección del
+// espacio de mundo; el renderer lo evalúa en cada pixel que no cubrió ningún cuerpo opaco,
+// así el cielo gira con la cámara pero no se mueve al desplazarla (está en el infinito).
+//
+// `ProceduralSky` genera las estrellas a partir de una semilla: cada cara del cubo se divide
+// en celdas y cada celda tiene como mucho una estrella, con posición, magnitud, temperatura
+// y parpadeo sacados de un hash de la celda. Las estrellas se calculan una vez al crear el
+// cielo y en cada frame solo cambia el parpadeo. La nebulosa es ruido fractal que se hornea
+// también una sola vez en un cube map de baja resolución.
+//
+// `ImageSky` usa una imagen: un mapa equirectangular o un cube map de seis caras.
+
+use nalgebra_glm::Vec3;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use crate::color::Color;
+use crate::cubemap::{face_and_uv, face_direction};
+use crate::noise::NoiseSettings;
+use crate::texture::{CubeMap, Sampler, Texture};
+
+pub trait Skybox: Send + Sync {
+    // `direction` está normalizada; `pixel_angle` es el ángulo que cubre un pixel en radianes,
+    // para que las estrellas ocupen uno o dos pixeles con cualquier resolución y campo de visión
+    fn sample(&self, direction: &Vec3, pixel_angle: f32, time: u32) -> Color;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StarfieldSettings {
+    pub seed: u64,
+    // Celdas por lado en cada cara del cubo
+    pub cells: usize,
+    // Probabilidad de que una celda tenga estrella
+    pub density: f32,
+    // Brillo de una estrella de magnitud 0; puede pasar de 1 (HDR)
+    pub brightness: f32,
+    // Magnitud de las estrellas más débiles; las más brillantes son de magnitud 0
+    pub faintest_magnitude: f32,
+    // Amplitud del parpadeo, de 0 (fijas) a 1
+    pub twinkle: f32,
+}
+
+impl Default for StarfieldSettings {
+    fn default() -> Self {
+        StarfieldSettings { seed: 1, cells: 128, density: 0.15, brightness: 2.5, faintest_magnitude: 7.0, twinkle: 0.3 }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NebulaSettings {
+    pub noise: NoiseSettings,
+    // El ruido mezcla entre estos dos colores
+    pub colors: [Color; 2],
+    pub intensity: f32,
+    // Fracción del cielo cubierta por la nebulosa, de 0 a 1
+    pub coverage: f32,
+    // Texels por lado en cada cara del cube map
+    pub resolution: usize,
+}
+
+impl Default for NebulaSettings {
+    fn default() -> Self {
+        NebulaSettings {
+            noise: NoiseSettings::nebula(),
+            colors: [Color::new(96, 40, 150), Color::new(30, 100, 140)],
+            intensity: 0.2,
+            coverage: 0.5,
+            resolution: 128,
+        }
+    }
+}
+
+const NO_STAR: u32 = u32::MAX;
+
+const MIN_TEMPERATURE: f32 = 2500.0;
+const MAX_TEMPERATURE: f32 = 12000.0;
+
+// Radio de una estrella (desviación de la gaussiana) en pixeles; se dibuja hasta 3 radios
+const STAR_RADIUS: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Star {
+    // Normalizada
+    pub direction: Vec3,
+    pub magnitude: f32,
+    // En kelvin; `color` es `blackbody(temperature)`
+    pub temperature: f32,
+    pub color: Color,
+    // Fase (radianes) y velocidad (radianes por frame) del parpadeo
+    pub twinkle_phase: f32,
+    pub twinkle_speed: f32,
+}
+
+impl Star {
+    // Brillo en el frame `time`: cada 5 magnitudes es 100 veces más débil
+    pub fn brightness(&self, settings: &StarfieldSettings, time: u32) -> f32 {
+        let twinkle = 1.0 + settings.twinkle * (time as f32 * self.twinkle_speed + self.twinkle_phase).sin();
+        settings.brightness * 10f32.powf(-0.4 * self.magnitude) * twinkle.max(0.0)
+    }
+}
+
+pub struct ProceduralSky {
+    settings: StarfieldSettings,
+    stars: Vec<Star>,
+    // Índice en `stars` de la estrella de cada celda (NO_STAR si está vacía): seis caras de
+    // cells² en el orden de `face_and_uv`
+    cells: Vec<u32>,
+    nebula: Option<NebulaMap>,
+}
+
+impl ProceduralSky {
+    // Genera las estrellas y hornea la nebulosa, si hay
+    pub fn new(settings: StarfieldSettings, nebula: Option<&NebulaSettings>) -> Self {
+        let cells = settings.cells.max(1);
+        let settings = StarfieldSettings { cells, ..settings };
+        let mut stars = Vec::new();
+        let cells = (0..6 * cells * cells)
+            .map(|index| match generate_star(&settings, index / (cells * cells), index % cells, index / cells % cells) {
+                Some(star) => {
+                    stars.push(star);
+                    stars.len() as u32 - 1
+                }
+                None => NO_STAR,
+            })
+            .collect();
+        ProceduralSky { settings, stars, cells, nebula: nebula.map(NebulaMap::bake) }
+    }
+
+    pub fn settings(&self) -> &StarfieldSettings {
+        &self.settings
+    }
+
+    // La estrella de la celda (x, y) de la cara `face`, o None si la celda está vacía.
+    // Siempre es la misma para la misma semilla.
+    pub fn star(&self, face: usize, x: usize, y: usize) -> Option<&Star> {
+        let cells = self.settings.cells;
+        self.stars.get(self.cells[(face * cells + y) * cells + x] as usize)
+    }
+
+    // Luz de las estrellas en `direction`, que cae en (u, v) de la cara `face`. Se revisan
+    // las 2x2 celdas más cercanas (la de la dirección y sus vecinas del lado más cercano), así
+    // una estrella no se corta en el borde de su celda mientras su radio no pase de media
+    // celda; con pixeles más grandes que eso las estrellas se achican. Las celdas de las caras
+    // vecinas no se revisan.
+    fn starlight(&self, direction: &Vec3, (face, u, v): (usize, f32, f32), pixel_angle: f32, time: u32) -> Color {
+        let cells = self.settings.cells;
+        // Un ángulo θ alrededor de (s, t) = (2u - 1, 2v - 1) mueve (u, v) como mucho θ(1 + s² + t²)/2
+        let (s, t) = (u * 2.0 - 1.0, v * 2.0 - 1.0);
+        let half_cell = 1.0 / (cells as f32 * (1.0 + s * s + t * t));
+        let radius = (pixel_angle * STAR_RADIUS).min(half_cell / 3.0).max(1e-6);
+        let reach = radius * 3.0;
+
+        // `as usize` trunca, que es floor porque el valor ya no es negativo
+        let first = |coord: f32| ((coord * cells as f32 - 0.5).max(0.0) as usize).min(cells.saturating_sub(2));
+        let (first_x, first_y) = (first(u), first(v));
+
+        let mut light = Color::black();
+        for y in first_y..(first_y + 2).min(cells) {
+            for x in first_x..(first_x + 2).min(cells) {
+                let Some(star) = self.star(face, x, y) else {
+                    continue;
+                };
+                let distance = (direction - star.direction).magnitude();
+                if distance < reach {
+                    let weight = (-0.5 * (distance / radius).powi(2)).exp();
+                    light = light + star.color * (star.brightness(&self.settings, time) * weight);
+                }
+            }
+        }
+        light
+    }
+}
+
+impl Skybox for ProceduralSky {
+    fn sample(&self, direction: &Vec3, pixel_angle: f32, time: u32) -> Color {
+        let cube = face_and_uv(direction);
+        let nebula = match &self.nebula {
+            Some(nebula) => nebula.sample(cube),
+            None => Color::black(),
+        };
+        nebula + self.starlight(direction, cube, pixel_angle, time)
+    }
+}
+
+fn generate_star(settings: &StarfieldSettings, face: usize, x: usize, y: usize) -> Option<Star> {
+    let mut hash = CellHash::new(settings.seed, [face as u64, x as u64, y as u64]);
+    if hash.unit() >= settings.density {
+        return None;
+    }
+
+    let cells = settings.cells as f32;
+    let u = (x as f32 + hash.unit()) / cells;
+    let v = (y as f32 + hash.unit()) / cells;
+    let temperature = MIN_TEMPERATURE + (MAX_TEMPERATURE - MIN_TEMPERATURE) * hash.unit();
+    Some(Star {
+        direction: face_direction(face, u, v).normalize(),
+        // Hay más estrellas débiles que brillantes
+        magnitude: settings.faintest_magnitude * hash.unit().sqrt(),
+        temperature,
+        color: blackbody(temperature),
+        twinkle_phase: hash.unit() * TAU,
+        twinkle_speed: 0.02 + 0.1 * hash.unit(),
+    })
+}
+
+// Color de un cuerpo negro a `kelvin` grados (aproximación de Tanner Helland), con
+// luminancia 1 para que la temperatura solo cambie el tono y no el brillo
+pub fn blackbody(kelvin: f32) -> Color {
+    let t = kelvin.clamp(1000.0, 40000.0) / 100.0;
+    let red = if t <= 66.0 { 255.0 } else { 329.698_73 * (t - 60.0).powf(-0.133_204_76) };
+    let green = if t <= 66.0 {
+        99.470_8 * t.ln() - 161.119_57
+    } else {
+        288.122_17 * (t - 60.0).powf(-0.075_514_85)
+    };
+    let blue = if t >= 66.0 {
+        255.0
+    } else if t <= 19.0 {
+        0.0
+    } else {
+        138.517_73 * (t - 10.0).ln() - 305.044_8
+    };
+
+    let channel = |value: f32| value.clamp(0.0, 255.0) / 255.0;
+    let color = Color::from_srgb(channel(red), channel(green), channel(blue));
+    color * (1.0 / color.luminance())
+}
+
+// Nebulosa horneada: seis caras de resolution² texels en el orden de `face_and_uv`
+struct NebulaMap {
+    resolution: usize,
+    texels: Vec<Color>,
+}
+
+impl NebulaMap {
+    fn bake(settings: &NebulaSettings) -> Self {
+        let resolution = settings.resolution.max(1);
+        let noise = settings.noise.build();
+        let coverage = settings.coverage.clamp(0.0, 1.0);
+        let threshold = 1.0 - coverage;
+
+        let mut texels = Vec::with_capacity(6 * resolution * resolution);
+        for face in 0..6 {
+            for y in 0..resolution {
+                for x in 0..resolution {
+                    let u = (x as f32 + 0.5) / resolution as f32;
+                    let v = (y as f32 + 0.5) / resolution as f32;
+                    let d = face_direction(face, u, v).normalize();
+
+                    // La densidad y la mezcla de colores salen de dos zonas lejanas del mismo ruido
+                    let density = (noise.get_noise_3d(d.x, d.y, d.z) + 1.0) * 0.5;
+                    let mix = (noise.get_noise_3d(d.x + 31.7, d.y - 17.3, d.z + 5.1) + 1.0) * 0.5;
+                    let density = if coverage > 0.0 { ((density - threshold) / coverage).clamp(0.0, 1.0) } else { 0.0 };
+                    let density = density * density * (3.0 - 2.0 * density);
+
+                    let color = settings.colors[0].lerp(&settings.colors[1], mix);
+                    texels.push(color * (settings.intensity * density));
+                }
+            }
+        }
+
+        NebulaMap { resolution, texels }
+    }
+
+    // Interpolación bilineal dentro de la cara, sin cruzar a las vecinas
+    fn sample(&self, (face, u, v): (usize, f32, f32)) -> Color {
+        let last = self.resolution - 1;
+        let x = (u * self.resolution as f32 - 0.5).clamp(0.0, last as f32);
+        let y = (v * self.resolution as f32 - 0.5).clamp(0.0, last as f32);
+        let (x0, y0) = (x.floor() as usize, y.floor() as usize);
+        let (x1, y1) = ((x0 + 1).min(last), (y0 + 1).min(last));
+        let (fx, fy) = (x - x0 as f32, y - y0 as f32);
+
+        let texel = |x: usize, y: usize| self.texels[(face * self.resolution + y) * self.resolution + x];
+        let top = texel(x0, y0).lerp(&texel(x1, y0), fx);
+        let bottom = texel(x0, y1).lerp(&texel(x1, y1), fx);
+        top.lerp(&bottom, fy)
+    }
+}
+
+// Números pseudoaleatorios de una celda (splitmix64), independientes de las demás celdas
+struct CellHash(u64);
+
+impl CellHash {
+    fn new(seed: u64, cell: [u64; 3]) -> Self {
+        let mut hash = CellHash(seed);
+        for value in cell {
+            hash.0 = hash.next() ^ value;
+        }
+        hash
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniforme en [0, 1)
+    fn unit(&mut self) -> f32 {
+        (self.next() >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+pub enum SkyImage {
+    Equirectangular(Texture),
+    CubeMap(CubeMap),
+}
+
+// Cielo de una imagen; `intensity` la multiplica, para subir su brillo por encima de 1 (HDR)
+pub struct ImageSky {
+    pub image: SkyImage,
+    pub sampler: Sampler,
+    pub intensity: f32,
+}
+
+impl ImageSky {
+    pub fn new(image: SkyImage) -> Self {
+        ImageSky { image, sampler: Sampler::default(), intensity: 1.0 }
+    }
+}
+
+impl Skybox for ImageSky {
+    // El LOD sale del ángulo del pixel comparado con los texels por radián de la imagen
+    fn sample(&self, direction: &Vec3, pixel_angle: f32, _time: u32) -> Color {
+        let color = match &self.image {
+            SkyImage::Equirectangular(texture) => {
+                let lod = (pixel_angle * texture.width() as f32 / TAU).log2();
+                texture.sample_equirectangular_lod(direction, lod, &self.sampler)
+            }
+            SkyImage::CubeMap(cube_map) => {
+                let lod = (pixel_angle * cube_map.face(0).width() as f32 / FRAC_PI_2).log2();
+                cube_map.sample_lod(direction, lod, &self.sampler)
+            }
+        };
+        color * self.intensity
+    }
+}
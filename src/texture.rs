@@ -0,0 +1,506 @@
+// texture.rs
+//
+// Texturas cargadas de PNG o PPM. Los texels se guardan como colores lineales (los archivos
+// son sRGB, igual que `Color::new`), así el filtrado mezcla en el mismo espacio que la
+// iluminación. Las coordenadas (u, v) van de 0 a 1 con (0, 0) en la esquina superior
+// izquierda de la imagen, como las UVs de `Obj`. Al crear una textura se genera su cadena de
+// mipmaps, que el filtro trilineal usa según el tamaño del pixel sobre la textura (LOD).
+
+use nalgebra_glm::{Vec2, Vec3};
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use serde::Deserialize;
+
+use crate::color::Color;
+use crate::cubemap::face_and_uv;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Filter {
+    // El texel más cercano
+    Nearest,
+    // Promedio ponderado de los 4 texels más cercanos
+    #[default]
+    Bilinear,
+    // Bilineal en los dos mipmaps más cercanos al LOD, mezclados entre sí
+    Trilinear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WrapMode {
+    // La imagen se repite (lo normal en la longitud de un planeta)
+    #[default]
+    Repeat,
+    // Se repite reflejada, sin costuras en los bordes
+    MirroredRepeat,
+    // Fuera de la imagen se usa el texel del borde
+    ClampToEdge,
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Filter::Nearest => "nearest",
+            Filter::Bilinear => "bilinear",
+            Filter::Trilinear => "trilinear",
+        })
+    }
+}
+
+impl FromStr for Filter {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "nearest" => Ok(Filter::Nearest),
+            "bilinear" => Ok(Filter::Bilinear),
+            "trilinear" => Ok(Filter::Trilinear),
+            _ => Err(format!("unknown texture filter '{}' (expected nearest, bilinear or trilinear)", value)),
+        }
+    }
+}
+
+// Cómo se lee una textura: filtro y repetición en cada eje. `anisotropy` es el máximo de
+// muestras trilineales a lo largo del eje largo del pixel (0 o 1 la desactivan).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Sampler {
+    pub filter: Filter,
+    pub wrap_u: WrapMode,
+    pub wrap_v: WrapMode,
+    pub anisotropy: u32,
+}
+
+impl Sampler {
+    pub fn new(filter: Filter, wrap: WrapMode) -> Self {
+        Sampler { filter, wrap_u: wrap, wrap_v: wrap, anisotropy: 0 }
+    }
+
+    pub fn with_anisotropy(self, anisotropy: u32) -> Self {
+        Sampler { anisotropy, ..self }
+    }
+}
+
+#[derive(Debug)]
+pub enum TextureError {
+    Io { path: PathBuf, source: io::Error },
+    Decode { path: PathBuf, message: String },
+    // Dimensiones o texels que no forman una textura, sin archivo de por medio
+    Size { message: String },
+}
+
+impl fmt::Display for TextureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TextureError::Io { path, source } => write!(f, "{}: {}", path.display(), source),
+            TextureError::Decode { path, message } => write!(f, "{}: {}", path.display(), message),
+            TextureError::Size { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for TextureError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            TextureError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+// Texturas de un cuerpo por nombre ("surface", "clouds", ...)
+pub type Textures = HashMap<String, Arc<Texture>>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Texture {
+    // levels[0] es la imagen original y cada nivel siguiente mide la mitad
+    levels: Vec<MipLevel>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct MipLevel {
+    width: usize,
+    height: usize,
+    texels: Vec<Color>,
+}
+
+impl Texture {
+    // `texels` va por filas, de arriba hacia abajo
+    pub fn new(width: usize, height: usize, texels: Vec<Color>) -> Result<Self, TextureError> {
+        if width == 0 || height == 0 {
+            return Err(TextureError::Size { message: "textures need at least one texel".to_string() });
+        }
+        if texels.len() != width * height {
+            let message = format!("a {}x{} texture needs {} texels, got {}", width, height, width * height, texels.len());
+            return Err(TextureError::Size { message });
+        }
+
+        let mut levels = vec![MipLevel { width, height, texels }];
+        while let Some(level) = levels.last().filter(|level| level.width > 1 || level.height > 1) {
+            levels.push(level.downsample());
+        }
+        Ok(Texture { levels })
+    }
+
+    pub fn from_fn(width: usize, height: usize, texel: impl Fn(usize, usize) -> Color) -> Result<Self, TextureError> {
+        let texels = (0..width * height).map(|index| texel(index % width, index / width)).collect();
+        Texture::new(width, height, texels)
+    }
+
+    // Elige el formato según la extensión (.png o .ppm)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
+        let path = path.as_ref();
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("ppm") => Texture::load_ppm(path),
+            _ => Texture::load_png(path),
+        }
+    }
+
+    // PNG en escala de grises o color, con o sin alfa; las paletas y los 16 bits se convierten a 8
+    pub fn load_png<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
+        let path = path.as_ref();
+        let decode_error = |error: png::DecodingError| TextureError::Decode { path: path.to_path_buf(), message: error.to_string() };
+        let file = File::open(path).map_err(|source| TextureError::Io { path: path.to_path_buf(), source })?;
+
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(decode_error)?;
+        let mut bytes = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut bytes).map_err(decode_error)?;
+        bytes.truncate(info.buffer_size());
+
+        let texels = match info.color_type {
+            png::ColorType::Grayscale => bytes.iter().map(|&v| Color::new(v, v, v)).collect(),
+            png::ColorType::GrayscaleAlpha => bytes.chunks_exact(2).map(|p| Color::new_rgba(p[0], p[0], p[0], p[1])).collect(),
+            png::ColorType::Rgb => bytes.chunks_exact(3).map(|p| Color::new(p[0], p[1], p[2])).collect(),
+            png::ColorType::Rgba => bytes.chunks_exact(4).map(|p| Color::new_rgba(p[0], p[1], p[2], p[3])).collect(),
+            png::ColorType::Indexed => {
+                return Err(TextureError::Decode { path: path.to_path_buf(), message: "unexpanded palette".to_string() });
+            }
+        };
+        Texture::new(info.width as usize, info.height as usize, texels)
+            .map_err(|error| TextureError::Decode { path: path.to_path_buf(), message: error.to_string() })
+    }
+
+    // PPM binario (P6) o de texto (P3) con valores de hasta 255
+    pub fn load_ppm<P: AsRef<Path>>(path: P) -> Result<Self, TextureError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path).map_err(|source| TextureError::Io { path: path.to_path_buf(), source })?;
+        parse_ppm(&bytes).map_err(|message| TextureError::Decode { path: path.to_path_buf(), message })
+    }
+
+    pub fn width(&self) -> usize {
+        self.levels[0].width
+    }
+
+    pub fn height(&self) -> usize {
+        self.levels[0].height
+    }
+
+    pub fn texel(&self, x: usize, y: usize) -> Color {
+        self.levels[0].texel(x, y)
+    }
+
+    // Cantidad de niveles de la cadena de mipmaps, contando la imagen original
+    pub fn mip_levels(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn mip_size(&self, level: usize) -> (usize, usize) {
+        (self.levels[level].width, self.levels[level].height)
+    }
+
+    pub fn mip_texel(&self, level: usize, x: usize, y: usize) -> Color {
+        self.levels[level].texel(x, y)
+    }
+
+    // Lee el nivel 0, sin mipmaps
+    pub fn sample(&self, uv: Vec2, sampler: &Sampler) -> Color {
+        self.sample_lod(uv, 0.0, sampler)
+    }
+
+    // Lee con un LOD dado: 0 es la imagen original y cada unidad divide la resolución entre 2.
+    // Solo el filtro trilineal usa los mipmaps; un LOD que no es finito cuenta como 0.
+    pub fn sample_lod(&self, uv: Vec2, lod: f32, sampler: &Sampler) -> Color {
+        match sampler.filter {
+            Filter::Nearest | Filter::Bilinear => self.levels[0].sample(uv, sampler),
+            Filter::Trilinear => {
+                // Derivadas degeneradas pueden dar NaN o infinito; se lee el nivel 0
+                let lod = if lod.is_finite() { lod.clamp(0.0, (self.levels.len() - 1) as f32) } else { 0.0 };
+                let (level, t) = (lod.floor() as usize, lod.fract());
+                let fine = self.levels[level].sample(uv, sampler);
+                if t == 0.0 || level + 1 >= self.levels.len() {
+                    return fine;
+                }
+                fine.lerp(&self.levels[level + 1].sample(uv, sampler), t)
+            }
+        }
+    }
+
+    // Lee con las derivadas de pantalla de `uv` (ver `Fragment::tex_coords_dx`), que dan el
+    // tamaño del pixel sobre la textura y así el LOD. Con `anisotropy` el LOD sale del eje
+    // corto y se promedian varias muestras a lo largo del eje largo.
+    pub fn sample_grad(&self, uv: Vec2, uv_dx: Vec2, uv_dy: Vec2, sampler: &Sampler) -> Color {
+        if sampler.filter != Filter::Trilinear {
+            return self.sample(uv, sampler);
+        }
+
+        let size = Vec2::new(self.width() as f32, self.height() as f32);
+        let (length_x, length_y) = (uv_dx.component_mul(&size).norm(), uv_dy.component_mul(&size).norm());
+        let (major, minor) = (length_x.max(length_y), length_x.min(length_y));
+        if !major.is_finite() || major <= 0.0 {
+            return self.sample(uv, sampler);
+        }
+
+        let samples = if sampler.anisotropy > 1 && minor > 0.0 {
+            (major / minor).ceil().min(sampler.anisotropy as f32) as u32
+        } else {
+            1
+        };
+        let lod = (major / samples as f32).log2();
+        if samples == 1 {
+            return self.sample_lod(uv, lod, sampler);
+        }
+
+        // Muestras repartidas a lo largo del eje largo del pixel
+        let axis = if length_x >= length_y { uv_dx } else { uv_dy };
+        let mut average = self.sample_lod(uv - axis * 0.5 * (1.0 - 1.0 / samples as f32), lod, sampler);
+        for i in 1..samples {
+            let t = (i as f32 + 0.5) / samples as f32 - 0.5;
+            average = average.lerp(&self.sample_lod(uv + axis * t, lod, sampler), 1.0 / (i + 1) as f32);
+        }
+        average
+    }
+
+    // Lee la textura como un mapa equirectangular (longitud en u, latitud en v) visto desde
+    // adentro, como un cielo. u siempre se repite y v se recorta en los polos.
+    pub fn sample_equirectangular(&self, direction: &Vec3, sampler: &Sampler) -> Color {
+        self.sample_equirectangular_lod(direction, 0.0, sampler)
+    }
+
+    pub fn sample_equirectangular_lod(&self, direction: &Vec3, lod: f32, sampler: &Sampler) -> Color {
+        self.sample_lod(equirectangular_uv(direction), lod, &equirectangular_sampler(sampler))
+    }
+
+    // Igual que `sample_equirectangular` con el LOD de las derivadas de pantalla de la dirección
+    pub fn sample_equirectangular_grad(&self, direction: &Vec3, direction_dx: &Vec3, direction_dy: &Vec3, sampler: &Sampler) -> Color {
+        let (uv, uv_dx, uv_dy) = equirectangular_gradients(direction, direction_dx, direction_dy);
+        self.sample_grad(uv, uv_dx, uv_dy, &equirectangular_sampler(sampler))
+    }
+}
+
+impl MipLevel {
+    // Promedio de bloques de 2x2; en los lados impares el último texel se repite
+    fn downsample(&self) -> MipLevel {
+        let (width, height) = ((self.width / 2).max(1), (self.height / 2).max(1));
+        let mut texels = Vec::with_capacity(width * height);
+        for y in 0..height {
+            let (y0, y1) = ((2 * y).min(self.height - 1), (2 * y + 1).min(self.height - 1));
+            for x in 0..width {
+                let (x0, x1) = ((2 * x).min(self.width - 1), (2 * x + 1).min(self.width - 1));
+                // lerp también promedia el alfa, que la suma de colores no toca
+                let top = self.texel(x0, y0).lerp(&self.texel(x1, y0), 0.5);
+                let bottom = self.texel(x0, y1).lerp(&self.texel(x1, y1), 0.5);
+                texels.push(top.lerp(&bottom, 0.5));
+            }
+        }
+        MipLevel { width, height, texels }
+    }
+
+    fn texel(&self, x: usize, y: usize) -> Color {
+        self.texels[y * self.width + x]
+    }
+
+    fn sample(&self, uv: Vec2, sampler: &Sampler) -> Color {
+        let (x, y) = (uv.x * self.width as f32, uv.y * self.height as f32);
+        match sampler.filter {
+            Filter::Nearest => self.wrapped_texel(x.floor() as i64, y.floor() as i64, sampler),
+            Filter::Bilinear | Filter::Trilinear => {
+                // Los centros de los texels están en (i + 0.5, j + 0.5)
+                let (x, y) = (x - 0.5, y - 0.5);
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as i64, y0 as i64);
+
+                let top = self.wrapped_texel(x0, y0, sampler).lerp(&self.wrapped_texel(x0 + 1, y0, sampler), fx);
+                let bottom = self.wrapped_texel(x0, y0 + 1, sampler).lerp(&self.wrapped_texel(x0 + 1, y0 + 1, sampler), fx);
+                top.lerp(&bottom, fy)
+            }
+        }
+    }
+
+    fn wrapped_texel(&self, x: i64, y: i64, sampler: &Sampler) -> Color {
+        self.texel(wrap(x, self.width, sampler.wrap_u), wrap(y, self.height, sampler.wrap_v))
+    }
+}
+
+fn equirectangular_sampler(sampler: &Sampler) -> Sampler {
+    Sampler { wrap_u: WrapMode::Repeat, wrap_v: WrapMode::ClampToEdge, ..*sampler }
+}
+
+// Coordenadas equirectangulares de `direction` vista desde el centro de la esfera: -Z cae en
+// el centro de la imagen, +X a su derecha y +Y arriba. Para la superficie de un planeta, vista
+// desde afuera, se usa la dirección con z invertida.
+pub fn equirectangular_uv(direction: &Vec3) -> Vec2 {
+    let d = direction.normalize();
+    let u = 0.5 + d.x.atan2(-d.z) / TAU;
+    let v = 0.5 - d.y.clamp(-1.0, 1.0).asin() / PI;
+    Vec2::new(u, v)
+}
+
+// Coordenadas equirectangulares y sus derivadas de pantalla, a partir de las derivadas de la
+// dirección. La diferencia en u se toma por el camino corto, sin saltar en la costura.
+pub fn equirectangular_gradients(direction: &Vec3, direction_dx: &Vec3, direction_dy: &Vec3) -> (Vec2, Vec2, Vec2) {
+    let uv = equirectangular_uv(direction);
+    let difference = |offset: &Vec3| {
+        let mut delta = equirectangular_uv(&(direction + offset)) - uv;
+        delta.x -= delta.x.round();
+        delta
+    };
+    (uv, difference(direction_dx), difference(direction_dy))
+}
+
+fn wrap(coord: i64, size: usize, mode: WrapMode) -> usize {
+    let size = size as i64;
+    let wrapped = match mode {
+        WrapMode::Repeat => coord.rem_euclid(size),
+        WrapMode::MirroredRepeat => {
+            let period = coord.rem_euclid(2 * size);
+            if period < size { period } else { 2 * size - 1 - period }
+        }
+        WrapMode::ClampToEdge => coord.clamp(0, size - 1),
+    };
+    wrapped as usize
+}
+
+// Recorre el encabezado de un PPM: números y palabras separados por espacios, con
+// comentarios que van de # al final de la línea
+struct PpmReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl PpmReader<'_> {
+    fn token(&mut self) -> Result<&str, String> {
+        loop {
+            while self.bytes.get(self.position).is_some_and(u8::is_ascii_whitespace) {
+                self.position += 1;
+            }
+            if self.bytes.get(self.position) != Some(&b'#') {
+                break;
+            }
+            while self.bytes.get(self.position).is_some_and(|&byte| byte != b'\n') {
+                self.position += 1;
+            }
+        }
+
+        let start = self.position;
+        while self.bytes.get(self.position).is_some_and(|byte| !byte.is_ascii_whitespace()) {
+            self.position += 1;
+        }
+        if start == self.position {
+            return Err("unexpected end of file".to_string());
+        }
+        std::str::from_utf8(&self.bytes[start..self.position]).map_err(|_| "invalid PPM header".to_string())
+    }
+
+    fn number(&mut self, what: &str) -> Result<usize, String> {
+        self.token()?.parse().map_err(|_| format!("invalid PPM {}", what))
+    }
+}
+
+fn parse_ppm(bytes: &[u8]) -> Result<Texture, String> {
+    let mut reader = PpmReader { bytes, position: 0 };
+    let binary = match reader.token()? {
+        "P6" => true,
+        "P3" => false,
+        magic => return Err(format!("unsupported PPM type '{}' (expected P6 or P3)", magic)),
+    };
+    let (width, height) = (reader.number("width")?, reader.number("height")?);
+    let max_value = reader.number("maximum value")?;
+    if width == 0 || height == 0 {
+        return Err("PPM images must not be empty".to_string());
+    }
+    if max_value == 0 || max_value > 255 {
+        return Err(format!("unsupported PPM maximum value {} (expected 1 to 255)", max_value));
+    }
+
+    // Un encabezado con medidas enormes no debe desbordar el tamaño
+    let count = width.checked_mul(height).and_then(|texels| texels.checked_mul(3)).ok_or("PPM image is too large")?;
+    let values: Vec<usize> = if binary {
+        // Un solo espacio separa el encabezado de los datos
+        let start = reader.position + 1;
+        let end = start.checked_add(count).ok_or("PPM image is too large")?;
+        let data = bytes.get(start..end).ok_or("PPM data is shorter than the image")?;
+        data.iter().map(|&value| value as usize).collect()
+    } else {
+        (0..count).map(|_| reader.number("value")).collect::<Result<_, _>>()?
+    };
+
+    let channel = |value: usize| (value.min(max_value) * 255 / max_value) as u8;
+    let texels = values.chunks_exact(3).map(|p| Color::new(channel(p[0]), channel(p[1]), channel(p[2]))).collect();
+    Texture::new(width, height, texels).map_err(|error| error.to_string())
+}
+
+const FACE_NAMES: [&str; 6] = ["+X", "-X", "+Y", "-Y", "+Z", "-Z"];
+
+// Índice y mensaje de la primera cara que no es cuadrada o no mide lo mismo que +X
+fn check_faces(faces: &[Texture; 6]) -> Result<(), (usize, String)> {
+    let size = faces[0].width();
+    match faces.iter().position(|face| face.width() != size || face.height() != size) {
+        Some(index) => {
+            let face = &faces[index];
+            Err((index, format!(
+                "cube map face {} is {}x{}, but every face must be {}x{}",
+                FACE_NAMES[index], face.width(), face.height(), size, size
+            )))
+        }
+        None => Ok(()),
+    }
+}
+
+// Seis texturas cuadradas en el orden +X, -X, +Y, -Y, +Z, -Z, leídas por dirección
+#[derive(Debug, Clone, PartialEq)]
+pub struct CubeMap {
+    faces: [Texture; 6],
+}
+
+impl CubeMap {
+    // Las caras deben ser cuadradas y del mismo tamaño para que coincidan en las aristas
+    pub fn new(faces: [Texture; 6]) -> Result<Self, TextureError> {
+        check_faces(&faces).map_err(|(_, message)| TextureError::Size { message })?;
+        Ok(CubeMap { faces })
+    }
+
+    pub fn load<P: AsRef<Path>>(paths: &[P; 6]) -> Result<Self, TextureError> {
+        let mut faces = Vec::with_capacity(6);
+        for path in paths {
+            faces.push(Texture::load(path)?);
+        }
+        let faces: [Texture; 6] = faces.try_into().expect("six faces");
+        check_faces(&faces).map_err(|(face, message)| TextureError::Decode { path: paths[face].as_ref().to_path_buf(), message })?;
+        Ok(CubeMap { faces })
+    }
+
+    pub fn face(&self, face: usize) -> &Texture {
+        &self.faces[face]
+    }
+
+    // El filtro no cruza a las caras vecinas: los bordes se recortan
+    pub fn sample(&self, direction: &Vec3, sampler: &Sampler) -> Color {
+        self.sample_lod(direction, 0.0, sampler)
+    }
+
+    pub fn sample_lod(&self, direction: &Vec3, lod: f32, sampler: &Sampler) -> Color {
+        let (face, u, v) = face_and_uv(direction);
+        let sampler = Sampler { wrap_u: WrapMode::ClampToEdge, wrap_v: WrapMode::ClampToEdge, ..*sampler };
+        self.faces[face].sample_lod(Vec2::new(u, v), lod, &sampler)
+    }
+}
@@ -0,0 +1,158 @@
+// tiles.rs
+//
+// Rasterizado por tiles: el framebuffer se divide en cuadros de TILE_SIZE píxeles, cada
+// triángulo se agrega a la lista de los tiles que toca su bounding box y los tiles se
+// sombrean en paralelo. Cada tile procesa sus triángulos en el orden original, así que
+// el resultado es idéntico al del camino de un solo hilo.
+
+use std::sync::Mutex;
+use std::thread;
+
+use crate::color::Color;
+use crate::framebuffer::Framebuffer;
+use crate::planet_shader::PlanetShader;
+use crate::renderer::{render_in_place, RasterState, ShadingSink, Uniforms};
+use crate::triangle::{calculate_bounding_box, rasterize_in, PixelRect};
+use crate::vertex::Vertex;
+
+pub const TILE_SIZE: usize = 64;
+
+// Con menos triángulos crear los hilos y copiar los tiles cuesta más que sombrearlos
+pub const MIN_TILED_TRIANGLES: usize = 128;
+
+// Copia local del color y la profundidad de cada muestra de un tile, junto con los
+// triángulos que lo tocan
+struct Tile {
+    rect: PixelRect,
+    color: Vec<Color>,
+    depth: Vec<f32>,
+    triangles: Vec<usize>,
+}
+
+impl Tile {
+    // Rango de muestras de la fila `row` del tile dentro del framebuffer
+    fn row_range(&self, row: usize, framebuffer_width: usize, samples: usize) -> std::ops::Range<usize> {
+        let start = ((self.rect.min_y as usize + row) * framebuffer_width + self.rect.min_x as usize) * samples;
+        start..start + self.rect.width() * samples
+    }
+
+    fn load(&mut self, framebuffer: &mut Framebuffer) {
+        let (width, samples) = (framebuffer.width, framebuffer.samples());
+        let (color, depth) = framebuffer.sample_targets();
+        for row in 0..self.rect.height() {
+            let range = self.row_range(row, width, samples);
+            self.color.extend_from_slice(&color[range.clone()]);
+            self.depth.extend_from_slice(&depth[range]);
+        }
+    }
+
+    fn store(&self, framebuffer: &mut Framebuffer) {
+        let (width, samples) = (framebuffer.width, framebuffer.samples());
+        let row_len = self.rect.width() * samples;
+        let (color, depth) = framebuffer.sample_targets();
+        for row in 0..self.rect.height() {
+            let range = self.row_range(row, width, samples);
+            color[range.clone()].copy_from_slice(&self.color[row * row_len..(row + 1) * row_len]);
+            depth[range].copy_from_slice(&self.depth[row * row_len..(row + 1) * row_len]);
+        }
+    }
+}
+
+// Crea los tiles que cubren el framebuffer y reparte los triángulos entre ellos
+fn bin_triangles(framebuffer: &Framebuffer, triangles: &[[Vertex; 3]]) -> Vec<Tile> {
+    let tiles_x = framebuffer.width.div_ceil(TILE_SIZE);
+    let tiles_y = framebuffer.height.div_ceil(TILE_SIZE);
+
+    let mut tiles = Vec::with_capacity(tiles_x * tiles_y);
+    for ty in 0..tiles_y {
+        for tx in 0..tiles_x {
+            let (min_x, min_y) = (tx * TILE_SIZE, ty * TILE_SIZE);
+            let max_x = (min_x + TILE_SIZE).min(framebuffer.width) - 1;
+            let max_y = (min_y + TILE_SIZE).min(framebuffer.height) - 1;
+            tiles.push(Tile {
+                rect: PixelRect { min_x: min_x as i32, min_y: min_y as i32, max_x: max_x as i32, max_y: max_y as i32 },
+                color: Vec::new(),
+                depth: Vec::new(),
+                triangles: Vec::new(),
+            });
+        }
+    }
+
+    let (last_x, last_y) = (framebuffer.width as i32 - 1, framebuffer.height as i32 - 1);
+    for (index, tri) in triangles.iter().enumerate() {
+        let [a, b, c] = [0, 1, 2].map(|i| tri[i].transformed_position);
+        let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
+        if max_x < 0 || max_y < 0 || min_x > last_x || min_y > last_y {
+            continue;
+        }
+
+        let (first_tx, last_tx) = (min_x.max(0) as usize / TILE_SIZE, max_x.min(last_x) as usize / TILE_SIZE);
+        let (first_ty, last_ty) = (min_y.max(0) as usize / TILE_SIZE, max_y.min(last_y) as usize / TILE_SIZE);
+        for ty in first_ty..=last_ty {
+            for tx in first_tx..=last_tx {
+                tiles[ty * tiles_x + tx].triangles.push(index);
+            }
+        }
+    }
+
+    tiles.retain(|tile| !tile.triangles.is_empty());
+    tiles
+}
+
+// Rasteriza y sombrea triángulos ya proyectados a pantalla usando `state.threads` hilos.
+// Con pocos triángulos o un solo tile con trabajo se sombrea en el hilo actual, directo
+// sobre el framebuffer.
+pub fn render_tiles(
+    framebuffer: &mut Framebuffer,
+    uniforms: &Uniforms,
+    triangles: &[[Vertex; 3]],
+    shader: &dyn PlanetShader,
+    state: &RasterState,
+) {
+    if state.threads <= 1 || triangles.len() < MIN_TILED_TRIANGLES {
+        render_in_place(framebuffer, uniforms, triangles.iter().cloned(), shader, state);
+        return;
+    }
+
+    let mut tiles = bin_triangles(framebuffer, triangles);
+    if tiles.len() <= 1 {
+        render_in_place(framebuffer, uniforms, triangles.iter().cloned(), shader, state);
+        return;
+    }
+    let workers = state.threads.min(tiles.len());
+    let (antialiasing, samples) = (framebuffer.antialiasing(), framebuffer.samples());
+    for tile in &mut tiles {
+        tile.load(framebuffer);
+    }
+
+    // Cada hilo toma el siguiente tile libre hasta que no queden
+    let queue = Mutex::new(tiles.iter_mut());
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let Some(tile) = queue.lock().unwrap().next() else {
+                    break;
+                };
+
+                let rect = tile.rect;
+                let mut sink = ShadingSink {
+                    color: &mut tile.color,
+                    depth: &mut tile.depth,
+                    rect,
+                    samples,
+                    blend: state.blend,
+                    uniforms,
+                    shader,
+                };
+                for &index in &tile.triangles {
+                    let [v1, v2, v3] = &triangles[index];
+                    rasterize_in(v1, v2, v3, state.interpolation, antialiasing, &rect, &mut sink);
+                }
+            });
+        }
+    });
+
+    for tile in &tiles {
+        tile.store(framebuffer);
+    }
+}
@@ -0,0 +1,63 @@
+// tonemap.rs
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::color::Color;
+
+// Convierte colores HDR (lineales, sin límite superior) al rango [0, 1] de la pantalla
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToneMapping {
+    // Recorta cada canal a 1.0 (el comportamiento anterior)
+    #[default]
+    Clamp,
+    // c / (1 + c)
+    Reinhard,
+    // Aproximación de Narkowicz a la curva ACES filmic
+    Aces,
+    // 1 - e^(-c)
+    Exposure,
+}
+
+impl ToneMapping {
+    pub const ALL: [ToneMapping; 4] = [ToneMapping::Clamp, ToneMapping::Reinhard, ToneMapping::Aces, ToneMapping::Exposure];
+
+    // `exposure` multiplica el color antes de aplicar la curva
+    pub fn apply(self, color: Color, exposure: f32) -> Color {
+        let color = color * exposure;
+        match self {
+            ToneMapping::Clamp => color.map(|c| c.min(1.0)),
+            ToneMapping::Reinhard => color.map(|c| c / (1.0 + c)),
+            ToneMapping::Aces => color.map(|c| ((c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14)).clamp(0.0, 1.0)),
+            ToneMapping::Exposure => color.map(|c| 1.0 - (-c).exp()),
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|mode| *mode == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+}
+
+impl fmt::Display for ToneMapping {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ToneMapping::Clamp => "clamp",
+            ToneMapping::Reinhard => "reinhard",
+            ToneMapping::Aces => "aces",
+            ToneMapping::Exposure => "exposure",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ToneMapping {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        ToneMapping::ALL
+            .into_iter()
+            .find(|mode| mode.to_string() == s)
+            .ok_or_else(|| format!("unknown tone mapper '{}', expected clamp, reinhard, aces or exposure", s))
+    }
+}
@@ -1,65 +1,315 @@
 // triangle.rs
 
-use nalgebra_glm::{Vec3, dot, Vec2};
+use nalgebra_glm::{Vec3, Vec2};
+use crate::antialiasing::{AntiAliasing, MAX_SAMPLES};
 use crate::fragment::Fragment;
+use crate::varying::Varyings;
 use crate::vertex::Vertex;
-use crate::color::Color;
 
-pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex) -> Vec<Fragment> {
+// Cómo se interpolan los atributos dentro del triángulo
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Interpolation {
+  // Corrige la perspectiva usando la w de clip space de cada vértice
+  #[default]
+  Perspective,
+  // Baricéntricas de pantalla sin corregir (el comportamiento anterior)
+  Affine,
+}
+
+impl Interpolation {
+  pub fn toggle(self) -> Self {
+    match self {
+      Interpolation::Perspective => Interpolation::Affine,
+      Interpolation::Affine => Interpolation::Perspective,
+    }
+  }
+}
+
+// Recibe los fragmentos a medida que se rasterizan. `test` se consulta antes de
+// interpolar los atributos, lo que permite descartar píxeles ocultos (early depth test):
+// recibe la máscara de muestras cubiertas y la profundidad de cada una, y devuelve las
+// que siguen visibles.
+pub trait FragmentSink {
+  fn test(&mut self, _x: i32, _y: i32, coverage: u32, _sample_depths: &[f32]) -> u32 {
+    coverage
+  }
+
+  fn emit(&mut self, fragment: Fragment);
+
+  // Con false los fragmentos llegan sin derivadas de pantalla (en cero)
+  fn needs_derivatives(&self) -> bool {
+    true
+  }
+}
+
+// Cualquier closure sirve como sink sin prueba de profundidad
+impl<F: FnMut(Fragment)> FragmentSink for F {
+  fn emit(&mut self, fragment: Fragment) {
+    self(fragment)
+  }
+}
+
+// Versión que junta todos los fragmentos del triángulo en un Vec
+pub fn triangle(v1: &Vertex, v2: &Vertex, v3: &Vertex, interpolation: Interpolation) -> Vec<Fragment> {
   let mut fragments = Vec::new();
+  rasterize(v1, v2, v3, interpolation, &mut |fragment| fragments.push(fragment));
+  fragments
+}
+
+pub fn rasterize<S: FragmentSink + ?Sized>(v1: &Vertex, v2: &Vertex, v3: &Vertex, interpolation: Interpolation, sink: &mut S) {
+  rasterize_in(v1, v2, v3, interpolation, AntiAliasing::None, &PixelRect::UNBOUNDED, sink);
+}
+
+// Rectángulo de píxeles (inclusivo) al que se limita el rasterizado, p. ej. un tile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PixelRect {
+  pub min_x: i32,
+  pub min_y: i32,
+  pub max_x: i32,
+  pub max_y: i32,
+}
+
+impl PixelRect {
+  pub const UNBOUNDED: PixelRect = PixelRect { min_x: i32::MIN, min_y: i32::MIN, max_x: i32::MAX, max_y: i32::MAX };
+
+  pub fn width(&self) -> usize {
+    (self.max_x - self.min_x + 1) as usize
+  }
+
+  pub fn height(&self) -> usize {
+    (self.max_y - self.min_y + 1) as usize
+  }
+
+  pub fn contains(&self, x: i32, y: i32) -> bool {
+    x >= self.min_x && x <= self.max_x && y >= self.min_y && y <= self.max_y
+  }
+}
+
+// Bits de precisión sub-pixel de las coordenadas en punto fijo
+pub const SUBPIXEL_BITS: i64 = 8;
+const SUBPIXEL_ONE: i64 = 1 << SUBPIXEL_BITS;
+const SUBPIXEL_HALF: i64 = SUBPIXEL_ONE / 2;
+
+// Coordenada de pantalla en punto fijo (1/256 de pixel)
+fn to_fixed(value: f32) -> i64 {
+  (value * SUBPIXEL_ONE as f32).round() as i64
+}
+
+// Función de arista de a->b en punto fijo. El interior del triángulo es positivo; los
+// puntos exactamente sobre la arista solo cuentan si es una arista superior o izquierda,
+// así dos triángulos vecinos nunca comparten un pixel (o una muestra).
+struct Edge {
+  dx: i64,
+  dy: i64,
+  bias: i64,
+}
+
+impl Edge {
+  fn new(a: (i64, i64), b: (i64, i64)) -> Self {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    // Izquierda: el interior queda a la derecha (dE/dx > 0). Superior: arista horizontal
+    // con el interior debajo (dE/dy > 0, la Y de pantalla apunta hacia abajo).
+    let top_left = dy > 0 || (dy == 0 && dx < 0);
+    Edge { dx, dy, bias: if top_left { 0 } else { -1 } }
+  }
+
+  // Valor en el punto p (punto fijo); mismo resultado que edge_function(a, b, p)
+  fn at(a: (i64, i64), b: (i64, i64), p: (i64, i64)) -> i64 {
+    (p.0 - a.0) * (b.1 - a.1) - (p.1 - a.1) * (b.0 - a.0)
+  }
+
+  // Cuánto cambia el valor al desplazarse `offset` (punto fijo)
+  fn delta(&self, offset: (i64, i64)) -> i64 {
+    offset.0 * self.dy - offset.1 * self.dx
+  }
+}
+
+// Igual que `rasterize` pero solo visita los píxeles dentro de `rect`; los valores
+// de cada píxel no dependen del rectángulo, así que los tiles coinciden con el render completo.
+// La cobertura se evalúa en las muestras de `antialiasing`; con MSAA se sombrea una vez por
+// pixel y con SSAA una vez por muestra.
+pub fn rasterize_in<S: FragmentSink + ?Sized>(
+  v1: &Vertex,
+  v2: &Vertex,
+  v3: &Vertex,
+  interpolation: Interpolation,
+  antialiasing: AntiAliasing,
+  rect: &PixelRect,
+  sink: &mut S,
+) {
+  let fixed = |v: &Vertex| (to_fixed(v.transformed_position.x), to_fixed(v.transformed_position.y));
+
+  // Se ordenan los vértices para que el área sea positiva; así el interior siempre
+  // tiene las tres funciones de arista positivas, sin importar el winding
+  let (v1, mut v2, mut v3) = (v1, v2, v3);
+  let mut triangle_area = Edge::at(fixed(v1), fixed(v2), fixed(v3));
+  if triangle_area < 0 {
+    std::mem::swap(&mut v2, &mut v3);
+    triangle_area = -triangle_area;
+  }
+  if triangle_area == 0 {
+    return;
+  }
+
+  let (fa, fb, fc) = (fixed(v1), fixed(v2), fixed(v3));
   let (a, b, c) = (v1.transformed_position, v2.transformed_position, v3.transformed_position);
 
-  let (min_x, min_y, max_x, max_y) = calculate_bounding_box(&a, &b, &c);
+  // Bounding box en píxeles, recortado al rectángulo pedido
+  let min_x = (fa.0.min(fb.0).min(fc.0) >> SUBPIXEL_BITS).max(rect.min_x as i64);
+  let min_y = (fa.1.min(fb.1).min(fc.1) >> SUBPIXEL_BITS).max(rect.min_y as i64);
+  let max_x = (fa.0.max(fb.0).max(fc.0) >> SUBPIXEL_BITS).min(rect.max_x as i64);
+  let max_y = (fa.1.max(fb.1).max(fc.1) >> SUBPIXEL_BITS).min(rect.max_y as i64);
+  if min_x > max_x || min_y > max_y {
+    return;
+  }
 
-  let light_dir = Vec3::new(0.0, 0.0, 1.0);
+  // 1/w es lineal en pantalla; los atributos divididos entre w también
+  let inv_w = Vec3::new(1.0 / v1.clip_position.w, 1.0 / v2.clip_position.w, 1.0 / v3.clip_position.w);
 
-  let triangle_area = edge_function(&a, &b, &c);
+  // w1 es la arista opuesta a v1 (b->c), w2 la opuesta a v2 (c->a) y w3 la opuesta a v3 (a->b)
+  let edges = [Edge::new(fb, fc), Edge::new(fc, fa), Edge::new(fa, fb)];
+  let inside = |e: &[i64; 3]| (0..3).all(|k| e[k] + edges[k].bias >= 0);
+  let inv_area = 1.0 / triangle_area as f32;
+  let weights = |e: &[i64; 3]| (e[0] as f32 * inv_area, e[1] as f32 * inv_area, e[2] as f32 * inv_area);
+  let depth_at = |e: &[i64; 3]| {
+    let (w1, w2, w3) = weights(e);
+    a.z * w1 + b.z * w2 + c.z * w3
+  };
 
-  // Iterate over each pixel in the bounding box
-  for y in min_y..=max_y {
-    for x in min_x..=max_x {
-      let point = Vec3::new(x as f32 + 0.5, y as f32 + 0.5, 0.0);
+  // Desplazamiento de cada muestra (y del centro) respecto a la esquina del pixel
+  let positions = antialiasing.sample_positions();
+  let to_subpixel = |(x, y): (i64, i64)| (x * SUBPIXEL_ONE / 16, y * SUBPIXEL_ONE / 16);
+  let mut sample_deltas = [[0; 3]; MAX_SAMPLES];
+  for (deltas, &position) in sample_deltas.iter_mut().zip(positions) {
+    *deltas = [0, 1, 2].map(|k| edges[k].delta(to_subpixel(position)));
+  }
+  let center_deltas = [0, 1, 2].map(|k| edges[k].delta((SUBPIXEL_HALF, SUBPIXEL_HALF)));
+  let offset = |e: &[i64; 3], deltas: &[i64; 3]| [e[0] + deltas[0], e[1] + deltas[1], e[2] + deltas[2]];
+  let step_x = [0, 1, 2].map(|k| edges[k].dy * SUBPIXEL_ONE);
+  let step_y = [0, 1, 2].map(|k| -edges[k].dx * SUBPIXEL_ONE);
+
+  // Pesos para interpolar los atributos en el punto `e`
+  let attribute_weights = |e: &[i64; 3]| {
+    let (w1, w2, w3) = weights(e);
+    match interpolation {
+      Interpolation::Perspective => perspective_weights(w1, w2, w3, &inv_w),
+      Interpolation::Affine => (w1, w2, w3),
+    }
+  };
+  let tex_coords_at = |(p1, p2, p3): (f32, f32, f32)| v1.tex_coords * p1 + v2.tex_coords * p2 + v3.tex_coords * p3;
+  let position_at = |(p1, p2, p3): (f32, f32, f32)| v1.position * p1 + v2.position * p2 + v3.position * p3;
+
+  // Interpola los atributos en el punto `e`; `corner` es la esquina del pixel (x, y)
+  let derivatives = sink.needs_derivatives();
+  let interpolate = |x: i32, y: i32, corner: &[i64; 3], e: &[i64; 3], coverage: u32, sample_depths: &[f32; MAX_SAMPLES]| {
+    let (w1, w2, w3) = weights(e);
 
-      // Calculate barycentric coordinates
-      let (w1, w2, w3) = barycentric_coordinates(&point, &a, &b, &c, triangle_area);
+    // La profundidad ya está dividida entre w, así que se interpola en pantalla
+    let depth = a.z * w1 + b.z * w2 + c.z * w3;
 
-      // Check if the point is inside the triangle
-      if w1 >= 0.0 && w1 <= 1.0 && 
-         w2 >= 0.0 && w2 <= 1.0 &&
-         w3 >= 0.0 && w3 <= 1.0 {
+    let (p1, p2, p3) = attribute_weights(e);
 
-        // Interpolate normal
-        let normal = v1.transformed_normal * w1 + v2.transformed_normal * w2 + v3.transformed_normal * w3;
-        let normal = normal.normalize();
+    // Interpolate normal (en espacio de mundo)
+    let normal = v1.transformed_normal * p1 + v2.transformed_normal * p2 + v3.transformed_normal * p3;
+    let normal = normal.normalize();
 
-        // Calculate lighting intensity
-        let intensity = dot(&normal, &light_dir).max(0.0);
+    let color = v1.color * p1 + v2.color * p2 + v3.color * p3;
+    let tex_coords = tex_coords_at((p1, p2, p3));
 
-        // Create a gray color (unchanged)
-        let color = Color::new(100, 100, 100); // Medium gray
+    // Positions of the original vertex
+    let vertex_position = position_at((p1, p2, p3));
+    let world_position = v1.world_position * p1 + v2.world_position * p2 + v3.world_position * p3;
 
-        // Interpolate depth
-        let depth = a.z * w1 + b.z * w2 + c.z * w3;
+    let varyings = Varyings::interpolate(&v1.varyings, &v2.varyings, &v3.varyings, (p1, p2, p3));
 
-        // Positions of the original vertex
-        let vertex_position = v1.position * w1 + v2.position * w2 + v3.position * w3;
+    let mut fragment = Fragment {
+      color,
+      normal,
+      vertex_position,
+      tex_coords,
+      world_position,
+      varyings,
+      material: v1.material,
+      coverage,
+      sample_depths: *sample_depths,
+      ..Fragment::new(Vec2::new(x as f32, y as f32), depth)
+    };
 
-        fragments.push(Fragment::new(
-            Vec2::new(x as f32, y as f32),
-            color,
-            depth,
-            normal,
-            intensity,
-            vertex_position,
-        ));
+    // Derivadas por bloque de 2x2: diferencias entre los centros de los pixeles del bloque,
+    // aunque caigan fuera del triángulo (los atributos se extrapolan, como en una GPU)
+    if derivatives {
+      let (quad_x, quad_y) = ((x & 1) as i64, (y & 1) as i64);
+      let origin = [0, 1, 2].map(|k| corner[k] + center_deltas[k] - step_x[k] * quad_x - step_y[k] * quad_y);
+      let right = attribute_weights(&offset(&origin, &step_x));
+      let below = attribute_weights(&offset(&origin, &step_y));
+      let origin = attribute_weights(&origin);
+      fragment.tex_coords_dx = tex_coords_at(right) - tex_coords_at(origin);
+      fragment.tex_coords_dy = tex_coords_at(below) - tex_coords_at(origin);
+      fragment.vertex_position_dx = position_at(right) - position_at(origin);
+      fragment.vertex_position_dy = position_at(below) - position_at(origin);
+    }
+    fragment
+  };
+
+  let origin = (min_x << SUBPIXEL_BITS, min_y << SUBPIXEL_BITS);
+  let mut row = [Edge::at(fb, fc, origin), Edge::at(fc, fa, origin), Edge::at(fa, fb, origin)];
+
+  // Recorre el bounding box sumando los incrementos de cada arista
+  for y in min_y..=max_y {
+    let mut e = row;
+    for x in min_x..=max_x {
+      let mut coverage = 0;
+      let mut sample_depths = [0.0; MAX_SAMPLES];
+      for (s, deltas) in sample_deltas.iter().enumerate().take(positions.len()) {
+        let sample = offset(&e, deltas);
+        if inside(&sample) {
+          coverage |= 1 << s;
+          sample_depths[s] = depth_at(&sample);
+        }
       }
+
+      if coverage != 0 {
+        let (x, y) = (x as i32, y as i32);
+        let coverage = sink.test(x, y, coverage, &sample_depths[..positions.len()]);
+
+        if coverage != 0 && antialiasing.per_sample_shading() {
+          for (s, deltas) in sample_deltas.iter().enumerate().take(positions.len()) {
+            if coverage & (1 << s) != 0 {
+              sink.emit(interpolate(x, y, &e, &offset(&e, deltas), 1 << s, &sample_depths));
+            }
+          }
+        } else if coverage != 0 {
+          // Se sombrea en el centro del pixel; si el centro queda fuera del triángulo se
+          // usa la primera muestra visible para no extrapolar los atributos
+          let center = offset(&e, &center_deltas);
+          let point = if inside(&center) {
+            center
+          } else {
+            offset(&e, &sample_deltas[coverage.trailing_zeros() as usize])
+          };
+          sink.emit(interpolate(x, y, &e, &point, coverage, &sample_depths));
+        }
+      }
+
+      for (value, edge) in e.iter_mut().zip(&edges) {
+        *value += edge.dy * SUBPIXEL_ONE;
+      }
+    }
+
+    for (value, edge) in row.iter_mut().zip(&edges) {
+      *value -= edge.dx * SUBPIXEL_ONE;
     }
   }
+}
 
-  fragments
+// Pesos corregidos: (w_i / w_clip_i) normalizados para que sumen 1
+pub fn perspective_weights(w1: f32, w2: f32, w3: f32, inv_w: &Vec3) -> (f32, f32, f32) {
+  let (q1, q2, q3) = (w1 * inv_w.x, w2 * inv_w.y, w3 * inv_w.z);
+  let sum = q1 + q2 + q3;
+  (q1 / sum, q2 / sum, q3 / sum)
 }
 
-fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
+pub fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i32) {
     let min_x = v1.x.min(v2.x).min(v3.x).floor() as i32;
     let min_y = v1.y.min(v2.y).min(v3.y).floor() as i32;
     let max_x = v1.x.max(v2.x).max(v3.x).ceil() as i32;
@@ -68,14 +318,6 @@ fn calculate_bounding_box(v1: &Vec3, v2: &Vec3, v3: &Vec3) -> (i32, i32, i32, i3
     (min_x, min_y, max_x, max_y)
 }
 
-fn barycentric_coordinates(p: &Vec3, a: &Vec3, b: &Vec3, c: &Vec3, area: f32) -> (f32, f32, f32) {
-    let w1 = edge_function(b, c, p) / area;
-    let w2 = edge_function(c, a, p) / area;
-    let w3 = edge_function(a, b, p) / area;
-
-    (w1, w2, w3)
-}
-
-fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
+pub fn edge_function(a: &Vec3, b: &Vec3, c: &Vec3) -> f32 {
     (c.x - a.x) * (b.y - a.y) - (c.y - a.y) * (b.x - a.x)
 }
\ No newline at end of file
@@ -0,0 +1,97 @@
+// varying.rs
+//
+// Atributos extra por vértice ("varyings"). El vertex shader los escribe en
+// `Vertex::varyings`, el recorte y el rasterizador los interpolan igual que las UVs (con
+// corrección de perspectiva) y el fragment shader los lee en `Fragment::varyings`. Así un
+// atributo nuevo no obliga a tocar Vertex, Fragment ni el rasterizador.
+//
+// Como en GLSL, cada varying tiene una posición (`location`) que acuerdan las dos etapas del
+// shader; un Vec3 en la posición 2 ocupa los floats 2, 3 y 4. No se guardan nombres para que
+// la copia en cada fragmento sea barata.
+
+use nalgebra_glm::{Vec2, Vec3};
+
+use crate::color::Color;
+
+// Cantidad de floats disponibles entre todos los varyings
+pub const MAX_VARYINGS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Varyings {
+    values: [f32; MAX_VARYINGS],
+    // Floats usados (hasta la última posición escrita); solo esos se interpolan
+    len: u8,
+}
+
+impl Varyings {
+    pub fn new() -> Self {
+        Varyings::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+
+    pub fn set_float(&mut self, location: usize, value: f32) {
+        self.set(location, &[value]);
+    }
+
+    pub fn set_vec2(&mut self, location: usize, value: Vec2) {
+        self.set(location, &[value.x, value.y]);
+    }
+
+    pub fn set_vec3(&mut self, location: usize, value: Vec3) {
+        self.set(location, &[value.x, value.y, value.z]);
+    }
+
+    // Se interpola en lineal, con el alfa
+    pub fn set_color(&mut self, location: usize, value: Color) {
+        self.set(location, &[value.r(), value.g(), value.b(), value.a()]);
+    }
+
+    // Las posiciones que nadie escribió valen 0
+    pub fn float(&self, location: usize) -> f32 {
+        self.values[location]
+    }
+
+    pub fn vec2(&self, location: usize) -> Vec2 {
+        Vec2::new(self.values[location], self.values[location + 1])
+    }
+
+    pub fn vec3(&self, location: usize) -> Vec3 {
+        Vec3::new(self.values[location], self.values[location + 1], self.values[location + 2])
+    }
+
+    pub fn color(&self, location: usize) -> Color {
+        let v = &self.values[location..location + 4];
+        Color::from_float(v[0], v[1], v[2]).with_alpha(v[3])
+    }
+
+    // Combinación de tres vértices con los pesos `(p1, p2, p3)` (ya corregidos por perspectiva)
+    pub fn interpolate(a: &Varyings, b: &Varyings, c: &Varyings, (p1, p2, p3): (f32, f32, f32)) -> Varyings {
+        let mut result = Varyings { len: a.len.max(b.len).max(c.len), ..Varyings::default() };
+        for i in 0..result.len() {
+            result.values[i] = a.values[i] * p1 + b.values[i] * p2 + c.values[i] * p3;
+        }
+        result
+    }
+
+    pub fn lerp(&self, other: &Varyings, t: f32) -> Varyings {
+        let mut result = Varyings { len: self.len.max(other.len), ..Varyings::default() };
+        for i in 0..result.len() {
+            result.values[i] = self.values[i] + (other.values[i] - self.values[i]) * t;
+        }
+        result
+    }
+
+    fn set(&mut self, location: usize, values: &[f32]) {
+        let end = location + values.len();
+        assert!(end <= MAX_VARYINGS, "varyings only have {} floats, location {} needs {}", MAX_VARYINGS, location, end);
+        self.values[location..end].copy_from_slice(values);
+        self.len = self.len.max(end as u8);
+    }
+}
@@ -1,7 +1,9 @@
 // vertex.rs
 
-use nalgebra_glm::{Vec2, Vec3};
+use nalgebra_glm::{Vec2, Vec3, Vec4};
 use crate::color::Color;
+use crate::material::NO_MATERIAL;
+use crate::varying::Varyings;
 
 #[derive(Clone, Debug)]
 pub struct Vertex {
@@ -9,8 +11,15 @@ pub struct Vertex {
   pub normal: Vec3,
   pub tex_coords: Vec2,
   pub color: Color,
+  pub clip_position: Vec4,
   pub transformed_position: Vec3,
   pub transformed_normal: Vec3,
+  // Posición en espacio de mundo (model_matrix * position), la calcula el vertex shader
+  pub world_position: Vec3,
+  // Atributos extra que escribe el vertex shader (ver varying.rs)
+  pub varyings: Varyings,
+  // Índice en los materiales del cuerpo (ver material.rs); no se interpola
+  pub material: u32,
 }
 
 impl Vertex {
@@ -20,8 +29,12 @@ impl Vertex {
 			normal,
 			tex_coords,
 			color: Color::black(),
+			clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
 			transformed_position: position,
 			transformed_normal: normal,
+			world_position: position,
+			varyings: Varyings::new(),
+			material: NO_MATERIAL,
 		}
 	}
 
@@ -31,8 +44,12 @@ impl Vertex {
 			normal: Vec3::new(0.0, 0.0, 0.0),
 			tex_coords: Vec2::new(0.0, 0.0),
 			color,
+			clip_position: Vec4::new(position.x, position.y, position.z, 1.0),
 			transformed_position: Vec3::new(0.0, 0.0, 0.0),
 			transformed_normal: Vec3::new(0.0, 0.0, 0.0),
+			world_position: position,
+			varyings: Varyings::new(),
+			material: NO_MATERIAL,
 		}
 	}
 
@@ -40,6 +57,22 @@ impl Vertex {
 		self.transformed_position = position;
 		self.transformed_normal = normal;
 	}
+
+	// Interpola todos los atributos; se usa al recortar triángulos contra el frustum
+	pub fn lerp(&self, other: &Vertex, t: f32) -> Vertex {
+		Vertex {
+			position: self.position + (other.position - self.position) * t,
+			normal: self.normal + (other.normal - self.normal) * t,
+			tex_coords: self.tex_coords + (other.tex_coords - self.tex_coords) * t,
+			color: self.color.lerp(&other.color, t),
+			clip_position: self.clip_position + (other.clip_position - self.clip_position) * t,
+			transformed_position: self.transformed_position + (other.transformed_position - self.transformed_position) * t,
+			transformed_normal: self.transformed_normal + (other.transformed_normal - self.transformed_normal) * t,
+			world_position: self.world_position + (other.world_position - self.world_position) * t,
+			varyings: self.varyings.lerp(&other.varyings, t),
+			material: self.material,
+		}
+	}
 }
 
 impl Default for Vertex {
@@ -49,8 +82,12 @@ impl Default for Vertex {
 			normal: Vec3::new(0.0, 1.0, 0.0),
 			tex_coords: Vec2::new(0.0, 0.0),
 			color: Color::black(),
+			clip_position: Vec4::new(0.0, 0.0, 0.0, 1.0),
 			transformed_position: Vec3::new(0.0, 0.0, 0.0),
 			transformed_normal: Vec3::new(0.0, 1.0, 0.0),
+			world_position: Vec3::new(0.0, 0.0, 0.0),
+			varyings: Varyings::new(),
+			material: NO_MATERIAL,
 		}
 	}
 }
\ No newline at end of file
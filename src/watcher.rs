@@ -0,0 +1,41 @@
+// watcher.rs
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+// Detecta cambios en archivos comparando su fecha de modificación en cada consulta
+#[derive(Default)]
+pub struct FileWatcher {
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl FileWatcher {
+    pub fn new() -> Self {
+        FileWatcher { files: Vec::new() }
+    }
+
+    pub fn watch<P: AsRef<Path>>(&mut self, path: P) {
+        let path = path.as_ref().to_path_buf();
+        let modified = modified_time(&path);
+        self.files.push((path, modified));
+    }
+
+    // Archivos modificados desde la última consulta. Si un archivo desaparece por un
+    // momento (algunos editores guardan borrando y renombrando) se espera a que vuelva.
+    pub fn changed(&mut self) -> Vec<PathBuf> {
+        let mut changed = Vec::new();
+        for (path, last_modified) in &mut self.files {
+            let modified = modified_time(path);
+            if modified.is_some() && modified != *last_modified {
+                *last_modified = modified;
+                changed.push(path.clone());
+            }
+        }
+        changed
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
@@ -0,0 +1,122 @@
+// antialiasing.rs
+
+mod common;
+
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+
+use graficas_lab4::{AntiAliasing, Color, Fragment, PlanetShader, Renderer, Samples, Uniforms};
+use graficas_lab4::framebuffer::Framebuffer;
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::triangle::{rasterize_in, Interpolation, PixelRect};
+use common::{camera_on_z, screen_vertex, sphere};
+
+const BACKGROUND: u32 = 0x000000;
+const WHITE: u32 = 0xFFFFFF;
+
+// Blanco constante, para distinguir los píxeles parcialmente cubiertos
+struct WhiteShader;
+
+impl PlanetShader for WhiteShader {
+    fn name(&self) -> &str {
+        "white"
+    }
+
+    fn fragment(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> Color {
+        Color::new(255, 255, 255)
+    }
+}
+
+fn render_sphere(antialiasing: AntiAliasing, threads: usize) -> Renderer {
+    let mut renderer = Renderer::new(320, 240);
+    renderer.raster.threads = threads;
+    renderer.framebuffer.set_background_color(BACKGROUND);
+    renderer.set_antialiasing(antialiasing);
+    renderer.clear();
+    renderer.set_camera(&camera_on_z(3.0));
+
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+    renderer.draw(&sphere(), model_matrix, &Arc::new(create_generic_noise()), &WhiteShader);
+    renderer
+}
+
+fn partial_pixels(renderer: &Renderer) -> usize {
+    renderer.framebuffer.buffer.iter().filter(|&&pixel| pixel != BACKGROUND && pixel != WHITE).count()
+}
+
+#[test]
+fn silhouette_pixels_are_blended_with_the_background() {
+    let aliased = render_sphere(AntiAliasing::None, 1);
+    assert_eq!(partial_pixels(&aliased), 0);
+
+    for antialiasing in [AntiAliasing::Msaa(Samples::X4), AntiAliasing::Ssaa(Samples::X4)] {
+        let smooth = render_sphere(antialiasing, 1);
+        assert!(partial_pixels(&smooth) > 50, "{}", antialiasing);
+
+        // Dentro de la silueta todas las muestras están cubiertas
+        let center = 120 * 320 + 160;
+        assert_eq!(smooth.framebuffer.buffer[center], WHITE);
+        assert!(smooth.framebuffer.zbuffer[center].is_finite());
+    }
+}
+
+// MSAA sombrea una vez por pixel que toca el triángulo; SSAA una vez por muestra cubierta
+#[test]
+fn msaa_shades_once_per_pixel_and_ssaa_per_sample() {
+    let (a, b, c) = (screen_vertex(1.3, 2.1), screen_vertex(40.7, 9.4), screen_vertex(12.2, 35.9));
+
+    for samples in [Samples::X2, Samples::X4, Samples::X8] {
+        let mut msaa = Vec::new();
+        let mut sink = |fragment: Fragment| msaa.push((fragment.position.x as i32, fragment.position.y as i32, fragment.coverage));
+        rasterize_in(&a, &b, &c, Interpolation::Perspective, AntiAliasing::Msaa(samples), &PixelRect::UNBOUNDED, &mut sink);
+
+        let mut ssaa = Vec::new();
+        let mut sink = |fragment: Fragment| ssaa.push((fragment.position.x as i32, fragment.position.y as i32, fragment.coverage));
+        rasterize_in(&a, &b, &c, Interpolation::Perspective, AntiAliasing::Ssaa(samples), &PixelRect::UNBOUNDED, &mut sink);
+
+        let mut pixels: Vec<_> = msaa.iter().map(|&(x, y, _)| (x, y)).collect();
+        pixels.dedup();
+        assert_eq!(pixels.len(), msaa.len());
+
+        let covered_samples: u32 = msaa.iter().map(|&(_, _, coverage)| coverage.count_ones()).sum();
+        assert_eq!(ssaa.len(), covered_samples as usize);
+        assert!(ssaa.iter().all(|&(_, _, coverage)| coverage.count_ones() == 1));
+        assert!(msaa.iter().any(|&(_, _, coverage)| coverage != (1 << samples.count()) - 1));
+    }
+}
+
+#[test]
+fn tiled_msaa_matches_single_threaded() {
+    for antialiasing in [AntiAliasing::Msaa(Samples::X8), AntiAliasing::Ssaa(Samples::X2)] {
+        let single = render_sphere(antialiasing, 1);
+        let tiled = render_sphere(antialiasing, 4);
+        assert!(single.framebuffer.buffer == tiled.framebuffer.buffer, "{}", antialiasing);
+    }
+}
+
+#[test]
+fn resolve_averages_the_samples_of_each_pixel() {
+    let mut framebuffer = Framebuffer::new(2, 1);
+    framebuffer.set_antialiasing(AntiAliasing::Msaa(Samples::X4));
+    framebuffer.clear();
+
+    let (color, depth) = framebuffer.sample_targets();
+    color[..2].copy_from_slice(&[Color::from_hex(0xFF0000); 2]);
+    depth[..2].copy_from_slice(&[0.75, 0.25]);
+    color[4..8].copy_from_slice(&[Color::from_hex(0x00FF00); 4]);
+    framebuffer.resolve();
+
+    // El promedio se hace en lineal: la mitad de rojo es 0xBC en sRGB
+    assert_eq!(framebuffer.buffer, vec![0xBC0000, 0x00FF00]);
+    assert_eq!(framebuffer.zbuffer[0], 0.25);
+    assert_eq!(framebuffer.zbuffer[1], f32::INFINITY);
+}
+
+#[test]
+fn modes_parse_from_their_names() {
+    for mode in AntiAliasing::ALL {
+        assert_eq!(mode.to_string().parse::<AntiAliasing>(), Ok(mode));
+    }
+    assert!("msaa3".parse::<AntiAliasing>().is_err());
+}
@@ -0,0 +1,164 @@
+// blend.rs
+
+mod common;
+
+use nalgebra_glm::Vec3;
+use std::path::Path;
+use std::sync::Arc;
+
+use graficas_lab4::{Body, BlendMode, Color, Fragment, PlanetShader, Renderer, Scene, ShaderRegistry, Uniforms, Vertex};
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::scene_file::parse_scene;
+use common::{camera_on_z, sphere};
+
+// Shader de color fijo para saber exactamente qué se mezcla
+struct FlatShader {
+    color: Color,
+    blend: BlendMode,
+}
+
+impl PlanetShader for FlatShader {
+    fn name(&self) -> &str {
+        "flat"
+    }
+
+    fn fragment(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> Color {
+        self.color
+    }
+
+    fn blend_mode(&self) -> BlendMode {
+        self.blend
+    }
+}
+
+fn flat_body(name: &str, mesh: &Arc<Vec<Vertex>>, color: Color, blend: BlendMode, z: f32) -> Body {
+    let shader = Box::new(FlatShader { color, blend });
+    Body::new(name, Arc::clone(mesh), shader, Arc::new(create_generic_noise())).with_position(Vec3::new(0.0, 0.0, z))
+}
+
+fn render(scene: &Scene) -> Renderer {
+    let mut renderer = Renderer::new(80, 60);
+    renderer.raster.threads = 1;
+    renderer.clear();
+    renderer.set_camera(&camera_on_z(5.0));
+    renderer.draw_scene(scene);
+    renderer
+}
+
+fn center(renderer: &Renderer) -> u32 {
+    renderer.framebuffer.buffer[30 * 80 + 40]
+}
+
+#[test]
+fn blend_modes_scale_with_alpha() {
+    let dst = Color::from_float(0.5, 0.5, 0.5);
+    let src = Color::from_float(1.0, 0.0, 0.25).with_alpha(0.5);
+
+    assert_eq!(BlendMode::Opaque.apply(dst, src), Color::from_float(1.0, 0.0, 0.25));
+    assert_eq!(BlendMode::Alpha.apply(dst, src), Color::from_float(0.75, 0.25, 0.375));
+    assert_eq!(BlendMode::Additive.apply(dst, src), Color::from_float(1.0, 0.5, 0.625));
+    assert_eq!(BlendMode::Multiply.apply(dst, src), Color::from_float(0.5, 0.25, 0.3125));
+    assert_eq!(BlendMode::Screen.apply(dst, src), Color::from_float(0.75, 0.5, 0.5625));
+
+    // Un fragmento completamente transparente no cambia nada
+    for mode in &BlendMode::ALL[1..] {
+        assert_eq!(mode.apply(dst, src.with_alpha(0.0)), dst, "{}", mode);
+    }
+}
+
+#[test]
+fn screen_blending_handles_hdr_colors() {
+    // Canales por encima de 1.0 no invierten el resultado ni lo oscurecen
+    let dst = Color::from_float(3.0, 0.5, 0.0);
+    let src = Color::from_float(0.5, 2.0, 4.0);
+
+    assert_eq!(dst.blend_screen(&src), Color::from_float(3.0, 1.0, 1.0));
+    assert_eq!(BlendMode::Screen.apply(dst, src.with_alpha(0.5)), Color::from_float(3.0, 0.75, 0.5));
+}
+
+#[test]
+fn rgba_constructors() {
+    assert_eq!(Color::new(10, 20, 30).a(), 1.0);
+    assert_eq!(Color::from_hex_rgba(0x10203080), Color::from_hex(0x102030).with_alpha(128.0 / 255.0));
+    assert_eq!(Color::new_rgba(10, 20, 30, 255), Color::new(10, 20, 30));
+    assert_eq!((Color::new(10, 20, 30).with_alpha(0.25) * 2.0).a(), 0.25);
+}
+
+// La pasada transparente prueba la profundidad pero no la escribe
+#[test]
+fn transparent_bodies_do_not_write_depth() {
+    let mesh = Arc::new(sphere());
+    let red = Color::from_float(1.0, 0.0, 0.0);
+    let glass = Color::from_float(0.0, 0.0, 1.0).with_alpha(0.5);
+
+    let mut opaque_only = Scene::new();
+    opaque_only.add(flat_body("planet", &mesh, red, BlendMode::Opaque, 0.0)).unwrap();
+    let opaque_only = render(&opaque_only);
+
+    let mut shell = Scene::new();
+    shell.add(flat_body("shell", &mesh, glass, BlendMode::Alpha, 1.0)).unwrap();
+    shell.add(flat_body("planet", &mesh, red, BlendMode::Opaque, 0.0)).unwrap();
+    let shell = render(&shell);
+
+    assert_eq!(shell.framebuffer.zbuffer, opaque_only.framebuffer.zbuffer);
+    assert_eq!(center(&shell), Color::from_float(0.5, 0.0, 0.5).to_hex());
+
+    // Detrás del planeta opaco no se ve
+    let mut hidden = Scene::new();
+    hidden.add(flat_body("shell", &mesh, glass, BlendMode::Alpha, -1.0)).unwrap();
+    hidden.add(flat_body("planet", &mesh, red, BlendMode::Opaque, 0.0)).unwrap();
+    assert_eq!(center(&render(&hidden)), center(&opaque_only));
+}
+
+// Las capas transparentes se ordenan de atrás hacia adelante sin importar el orden de la escena
+#[test]
+fn transparent_bodies_are_sorted_back_to_front() {
+    let mesh = Arc::new(sphere());
+    let near = Color::from_float(1.0, 0.0, 0.0).with_alpha(0.5);
+    let far = Color::from_float(0.0, 1.0, 0.0).with_alpha(0.5);
+
+    let mut declared_far_first = Scene::new();
+    declared_far_first.add(flat_body("far", &mesh, far, BlendMode::Alpha, 0.0)).unwrap();
+    declared_far_first.add(flat_body("near", &mesh, near, BlendMode::Alpha, 1.0)).unwrap();
+
+    let mut declared_near_first = Scene::new();
+    declared_near_first.add(flat_body("near", &mesh, near, BlendMode::Alpha, 1.0)).unwrap();
+    declared_near_first.add(flat_body("far", &mesh, far, BlendMode::Alpha, 0.0)).unwrap();
+
+    let expected = render(&declared_far_first);
+    assert_eq!(expected.framebuffer.buffer, render(&declared_near_first).framebuffer.buffer);
+
+    // El fondo es negro: verde al 50% y encima rojo al 50%
+    assert_eq!(center(&expected), Color::from_float(0.5, 0.25, 0.0).to_hex());
+}
+
+#[test]
+fn scene_files_choose_blend_modes() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let source = r##"
+        [[body]]
+        name = "gaia"
+        mesh = "assets/model/sphere.obj"
+        shader = "dynamic_surface"
+
+        [[body]]
+        name = "clouds"
+        mesh = "assets/model/sphere.obj"
+        shader = "clouds"
+        scale = 1.05
+        orbit = { parent = "gaia", semi_major_axis = 0.0, period = 0.0 }
+        parameters = { cloud_color = "#FFFFFFC0" }
+
+        [[body]]
+        name = "corona"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+        blend = "additive"
+    "##;
+    let scene = parse_scene(source, Path::new("blend.toml"), &registry).unwrap().scene;
+
+    assert_eq!(scene.find("gaia").unwrap().blend_mode(), BlendMode::Opaque);
+    assert_eq!(scene.find("clouds").unwrap().blend_mode(), BlendMode::Alpha);
+    assert_eq!(scene.find("clouds").unwrap().position, scene.find("gaia").unwrap().position);
+    assert_eq!(scene.find("corona").unwrap().blend_mode(), BlendMode::Additive);
+}
@@ -0,0 +1,132 @@
+// clipping.rs
+
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+use std::sync::Arc;
+
+use graficas_lab4::{Camera, CullMode, Obj, Renderer, ShaderRegistry, Vertex};
+use graficas_lab4::clipping::{clip_polygon, clip_to_frustum, clip_triangle, project_to_screen, MAX_CLIPPED_VERTICES};
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::noise::create_generic_noise;
+
+fn clip_vertex(x: f32, y: f32, z: f32, w: f32) -> Vertex {
+    let mut vertex = Vertex::new(Vec3::new(x, y, z), Vec3::new(0.0, 0.0, 1.0), Vec2::new(0.0, 0.0));
+    vertex.clip_position = Vec4::new(x, y, z, w);
+    vertex
+}
+
+fn inside(vertex: &Vertex) -> bool {
+    let p = vertex.clip_position;
+    let eps = 1e-4;
+    p.x.abs() <= p.w + eps && p.y.abs() <= p.w + eps && p.z.abs() <= p.w + eps
+}
+
+#[test]
+fn triangle_inside_the_frustum_is_unchanged() {
+    let triangles = clip_triangle(
+        &clip_vertex(-0.5, -0.5, 0.0, 1.0),
+        &clip_vertex(0.5, -0.5, 0.0, 1.0),
+        &clip_vertex(0.0, 0.5, 0.0, 1.0),
+    );
+    assert_eq!(triangles.len(), 1);
+    assert_eq!(triangles[0][2].clip_position, Vec4::new(0.0, 0.5, 0.0, 1.0));
+}
+
+#[test]
+fn triangle_behind_the_camera_is_discarded() {
+    let triangles = clip_triangle(
+        &clip_vertex(0.0, 0.0, -2.0, -1.0),
+        &clip_vertex(1.0, 0.0, -2.0, -1.0),
+        &clip_vertex(0.0, 1.0, -2.0, -1.0),
+    );
+    assert!(triangles.is_empty());
+}
+
+#[test]
+fn triangle_crossing_the_near_plane_is_split() {
+    // Un vértice detrás del plano cercano: queda un cuadrilátero, es decir dos triángulos
+    let polygon = clip_polygon(
+        &clip_vertex(-0.5, 0.0, 0.5, 1.0),
+        &clip_vertex(0.5, 0.0, 0.5, 1.0),
+        &clip_vertex(0.0, 0.0, -3.0, -1.0),
+    );
+    assert_eq!(polygon.len(), 4);
+    assert!(polygon.iter().all(inside));
+    assert!(polygon.iter().all(|vertex| vertex.clip_position.w > 0.0));
+
+    let triangles = clip_triangle(
+        &clip_vertex(-0.5, 0.0, 0.5, 1.0),
+        &clip_vertex(0.5, 0.0, 0.5, 1.0),
+        &clip_vertex(0.0, 0.0, -3.0, -1.0),
+    );
+    assert_eq!(triangles.len(), 2);
+}
+
+// Un triángulo enorme que cruza los seis planos cabe en el polígono de la pila
+#[test]
+fn clipped_polygons_fit_on_the_stack() {
+    let polygon = clip_to_frustum(
+        &clip_vertex(-40.0, -30.0, -5.0, 1.0),
+        &clip_vertex(40.0, -30.0, 5.0, 1.0),
+        &clip_vertex(0.0, 50.0, 0.0, 1.0),
+    );
+    assert!(polygon.len() >= 3 && polygon.len() <= MAX_CLIPPED_VERTICES);
+    assert!(polygon.vertices().iter().all(inside));
+    // El abanico de n vértices tiene n - 2 triángulos
+    let fan = polygon.len() - 2;
+    assert_eq!(polygon.into_iter().count(), fan);
+}
+
+#[test]
+fn attributes_are_interpolated_at_the_intersection() {
+    let mut a = clip_vertex(0.0, 0.0, 0.0, 1.0);
+    let mut b = clip_vertex(0.0, 0.0, 0.0, 1.0);
+    a.clip_position = Vec4::new(0.0, 0.0, 0.0, 1.0);
+    b.clip_position = Vec4::new(3.0, 0.0, 0.0, 1.0);
+    a.tex_coords = Vec2::new(0.0, 0.0);
+    b.tex_coords = Vec2::new(1.0, 0.0);
+    let c = clip_vertex(0.0, 0.5, 0.0, 1.0);
+
+    let polygon = clip_polygon(&a, &b, &c);
+    let on_edge = polygon
+        .iter()
+        .find(|vertex| (vertex.clip_position.x - 1.0).abs() < 1e-5 && vertex.clip_position.y.abs() < 1e-5)
+        .expect("intersection with the right plane");
+    assert!((on_edge.tex_coords.x - 1.0 / 3.0).abs() < 1e-5);
+}
+
+// Con la cámara dentro de un planeta o rozando su superficie ningún vértice recortado
+// queda con w <= 0 y todos caen dentro del framebuffer
+#[test]
+fn camera_inside_or_close_to_a_planet_renders_safely() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let shader = registry.create("rocky_planet").unwrap();
+    let mesh = Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array();
+    let noise = Arc::new(create_generic_noise());
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+
+    for eye in [Vec3::new(0.0, 0.0, 0.2), Vec3::new(0.0, 0.0, 0.52), Vec3::new(0.3, 0.1, 0.45)] {
+        // Desde dentro solo se ven caras traseras
+        let mut renderer = Renderer::new(160, 120);
+        renderer.raster.cull_mode = CullMode::None;
+        renderer.clear();
+        renderer.set_camera(&Camera::new(eye, Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+        renderer.uniforms.model_matrix = model_matrix;
+
+        let (width, height) = (renderer.width() as f32, renderer.height() as f32);
+        let mut clipped = 0;
+        for tri in mesh.chunks_exact(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| shader.vertex(&tri[i], &renderer.uniforms));
+            for mut vertex in clip_to_frustum(&a, &b, &c).vertices().iter().cloned() {
+                assert!(vertex.clip_position.w > 0.0, "w = {}", vertex.clip_position.w);
+                project_to_screen(&mut vertex, &renderer.uniforms.viewport_matrix);
+                let p = vertex.transformed_position;
+                assert!((-1e-3..=width + 1e-3).contains(&p.x) && (-1e-3..=height + 1e-3).contains(&p.y), "{:?}", p);
+                clipped += 1;
+            }
+        }
+        assert!(clipped > 0);
+
+        renderer.draw(&mesh, model_matrix, &noise, shader.as_ref());
+        assert!(renderer.framebuffer.zbuffer.iter().any(|depth| depth.is_finite()));
+    }
+}
@@ -0,0 +1,26 @@
+// common/mod.rs
+//
+// Utilidades compartidas por las pruebas de integración. Cada archivo de pruebas usa
+// solo algunas, así que las demás no cuentan como código muerto.
+
+#![allow(dead_code)]
+
+use nalgebra_glm::{Vec2, Vec3};
+
+use graficas_lab4::{Camera, Obj, Vertex};
+
+pub fn sphere() -> Vec<Vertex> {
+    Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array()
+}
+
+// Cámara sobre el eje +Z a `distance` del origen, mirando hacia él
+pub fn camera_on_z(distance: f32) -> Camera {
+    Camera::new(Vec3::new(0.0, 0.0, distance), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0))
+}
+
+// Vértice ya proyectado a (x, y) en pantalla, con profundidad 0.5
+pub fn screen_vertex(x: f32, y: f32) -> Vertex {
+    let mut vertex = Vertex::new(Vec3::new(x, y, 0.0), Vec3::new(0.0, 0.0, 1.0), Vec2::zeros());
+    vertex.transformed_position = Vec3::new(x, y, 0.5);
+    vertex
+}
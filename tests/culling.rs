@@ -0,0 +1,72 @@
+// culling.rs
+
+mod common;
+
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+
+use graficas_lab4::{CullMode, FrontFace, Renderer, ShaderRegistry};
+use graficas_lab4::culling::{is_culled, is_front_facing};
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::noise::create_generic_noise;
+use common::{camera_on_z, sphere};
+
+// Antihorario en NDC; en pantalla la Y apunta hacia abajo
+fn screen_ccw() -> [Vec3; 3] {
+    [Vec3::new(10.0, 50.0, 0.0), Vec3::new(50.0, 50.0, 0.0), Vec3::new(30.0, 10.0, 0.0)]
+}
+
+#[test]
+fn winding_decides_the_front_face() {
+    let [a, b, c] = screen_ccw();
+    assert!(is_front_facing(&a, &b, &c, FrontFace::CounterClockwise));
+    assert!(!is_front_facing(&a, &b, &c, FrontFace::Clockwise));
+    assert!(is_front_facing(&a, &c, &b, FrontFace::Clockwise));
+}
+
+#[test]
+fn cull_modes() {
+    let [a, b, c] = screen_ccw();
+    let ccw = FrontFace::CounterClockwise;
+    assert!(!is_culled(&a, &b, &c, CullMode::Back, ccw));
+    assert!(is_culled(&a, &c, &b, CullMode::Back, ccw));
+    assert!(is_culled(&a, &b, &c, CullMode::Front, ccw));
+    assert!(!is_culled(&a, &c, &b, CullMode::Front, ccw));
+    assert!(!is_culled(&a, &b, &c, CullMode::None, ccw));
+    assert!(!is_culled(&a, &c, &b, CullMode::None, ccw));
+}
+
+fn render_sphere(cull_mode: CullMode, front_face: FrontFace) -> Renderer {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let shader = registry.create("rocky_planet").unwrap();
+    let mesh = sphere();
+    let noise = Arc::new(create_generic_noise());
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+
+    let mut renderer = Renderer::new(160, 120);
+    renderer.raster.cull_mode = cull_mode;
+    renderer.raster.front_face = front_face;
+    renderer.clear();
+    renderer.set_camera(&camera_on_z(5.0));
+    renderer.draw(&mesh, model_matrix, &noise, shader.as_ref());
+    renderer
+}
+
+// Quitar las caras traseras de una esfera cerrada no cambia la imagen
+#[test]
+fn back_face_culling_keeps_the_visible_hemisphere() {
+    let culled = render_sphere(CullMode::Back, FrontFace::CounterClockwise);
+    let reference = render_sphere(CullMode::None, FrontFace::CounterClockwise);
+    assert_eq!(culled.framebuffer.buffer, reference.framebuffer.buffer);
+}
+
+// Con el orden invertido solo queda el hemisferio trasero, que está más lejos
+#[test]
+fn swapped_winding_draws_the_far_hemisphere() {
+    let near = render_sphere(CullMode::Back, FrontFace::CounterClockwise);
+    let far = render_sphere(CullMode::Back, FrontFace::Clockwise);
+
+    let center = 60 * 160 + 80;
+    assert!(far.framebuffer.zbuffer[center].is_finite());
+    assert!(far.framebuffer.zbuffer[center] > near.framebuffer.zbuffer[center]);
+}
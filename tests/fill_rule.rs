@@ -0,0 +1,115 @@
+// fill_rule.rs
+//
+// Los triángulos que comparten aristas deben cubrir cada pixel exactamente una vez:
+// sin huecos entre ellos y sin píxeles dibujados dos veces.
+
+mod common;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use graficas_lab4::{Fragment, Vertex};
+use graficas_lab4::triangle::{rasterize, Interpolation};
+use common::screen_vertex;
+
+const SIZE: usize = 64;
+
+// Cuántas veces se cubre cada pixel del área SIZE x SIZE
+fn coverage(triangles: &[[Vertex; 3]]) -> Vec<u32> {
+    let mut counts = vec![0; SIZE * SIZE];
+    for [a, b, c] in triangles {
+        rasterize(a, b, c, Interpolation::Perspective, &mut |fragment: Fragment| {
+            let (x, y) = (fragment.position.x as usize, fragment.position.y as usize);
+            counts[y * SIZE + x] += 1;
+        });
+    }
+    counts
+}
+
+// Malla de quads sobre todo el área, con los vértices interiores desplazados al azar
+fn jittered_grid(cells: usize, rng: &mut StdRng, flip_winding: bool) -> Vec<[Vertex; 3]> {
+    let step = SIZE as f32 / cells as f32;
+    let mut points = vec![vec![(0.0, 0.0); cells + 1]; cells + 1];
+    for (j, row) in points.iter_mut().enumerate() {
+        for (i, point) in row.iter_mut().enumerate() {
+            let interior = i > 0 && j > 0 && i < cells && j < cells;
+            let jitter = if interior { step * 0.4 } else { 0.0 };
+            *point = (
+                i as f32 * step + rng.gen_range(-jitter..=jitter),
+                j as f32 * step + rng.gen_range(-jitter..=jitter),
+            );
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for j in 0..cells {
+        for i in 0..cells {
+            let p = |i: usize, j: usize| screen_vertex(points[j][i].0, points[j][i].1);
+            let (p00, p10, p01, p11) = (p(i, j), p(i + 1, j), p(i, j + 1), p(i + 1, j + 1));
+            if flip_winding {
+                triangles.push([p00.clone(), p01.clone(), p10.clone()]);
+                triangles.push([p10, p01, p11]);
+            } else {
+                triangles.push([p00.clone(), p10.clone(), p01.clone()]);
+                triangles.push([p10, p11, p01]);
+            }
+        }
+    }
+    triangles
+}
+
+#[test]
+fn two_triangles_of_a_quad_cover_each_pixel_once() {
+    let s = SIZE as f32;
+    let quad = [
+        [screen_vertex(0.0, 0.0), screen_vertex(s, 0.0), screen_vertex(0.0, s)],
+        [screen_vertex(s, 0.0), screen_vertex(s, s), screen_vertex(0.0, s)],
+    ];
+    assert!(coverage(&quad).iter().all(|&count| count == 1));
+}
+
+// Abanico alrededor de un vértice compartido que cae justo en el centro de un pixel
+#[test]
+fn triangle_fan_has_no_gaps_or_overlaps() {
+    let center = screen_vertex(32.5, 32.5);
+    let s = SIZE as f32;
+    let rim = [(0.0, 0.0), (21.0, 0.0), (s, 0.0), (s, 40.25), (s, s), (10.75, s), (0.0, s), (0.0, 17.5)];
+
+    let fan: Vec<[Vertex; 3]> = (0..rim.len())
+        .map(|i| {
+            let (x0, y0) = rim[i];
+            let (x1, y1) = rim[(i + 1) % rim.len()];
+            [center.clone(), screen_vertex(x0, y0), screen_vertex(x1, y1)]
+        })
+        .collect();
+
+    assert!(coverage(&fan).iter().all(|&count| count == 1));
+}
+
+#[test]
+fn jittered_meshes_are_watertight() {
+    let mut rng = StdRng::seed_from_u64(14);
+    for cells in [3, 7, 16] {
+        for flip_winding in [false, true] {
+            let counts = coverage(&jittered_grid(cells, &mut rng, flip_winding));
+            let bad = counts.iter().filter(|&&count| count != 1).count();
+            assert_eq!(bad, 0, "{} cells, flipped {}: {} pixels not covered exactly once", cells, flip_winding, bad);
+        }
+    }
+}
+
+// Una arista horizontal o vertical sobre los centros de los píxeles pertenece solo
+// al triángulo de abajo / de la derecha
+#[test]
+fn pixels_on_a_shared_edge_go_to_the_top_left_owner() {
+    let above = [screen_vertex(0.0, 0.0), screen_vertex(8.0, 4.5), screen_vertex(0.0, 4.5)];
+    let below = [screen_vertex(0.0, 4.5), screen_vertex(8.0, 4.5), screen_vertex(0.0, 9.0)];
+
+    let mut rows_above = Vec::new();
+    rasterize(&above[0], &above[1], &above[2], Interpolation::Perspective, &mut |f: Fragment| rows_above.push(f.position.y));
+    let mut rows_below = Vec::new();
+    rasterize(&below[0], &below[1], &below[2], Interpolation::Perspective, &mut |f: Fragment| rows_below.push(f.position.y));
+
+    assert!(rows_above.iter().all(|&y| y < 4.0));
+    assert!(rows_below.contains(&4.0));
+}
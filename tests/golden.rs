@@ -0,0 +1,181 @@
+// golden.rs
+//
+// Renderiza sphere.obj con cada shader en modo headless y compara el resultado
+// contra las imágenes de referencia en tests/golden/.
+//
+// Para regenerar las referencias después de un cambio intencional:
+//     UPDATE_GOLDEN=1 cargo test --test golden
+
+use std::env;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const WIDTH: usize = 320;
+const HEIGHT: usize = 240;
+const TIME: u32 = 42;
+
+// Diferencia máxima permitida por canal y fracción de píxeles que la pueden superar
+const CHANNEL_TOLERANCE: u8 = 3;
+const MAX_MISMATCHED_FRACTION: f32 = 0.001;
+
+struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+fn manifest_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+}
+
+fn golden_path(shader: &str) -> PathBuf {
+    manifest_dir().join("tests/golden").join(format!("{}.png", shader))
+}
+
+fn output_dir() -> PathBuf {
+    let dir = manifest_dir().join("target/golden");
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// La salida de error del visor se captura: el aviso del .mtl que le falta a sphere.obj
+// ya lo comprueba tests/material.rs y aquí solo se muestra si el render falla
+fn render_shader(shader: &str, output: &Path) {
+    let result = Command::new(env!("CARGO_BIN_EXE_Graficas_lab4"))
+        .current_dir(manifest_dir())
+        .args(["--headless", "--frames", "1"])
+        .args(["--time", &TIME.to_string()])
+        .args(["--shader", shader])
+        .args(["--width", &WIDTH.to_string()])
+        .args(["--height", &HEIGHT.to_string()])
+        .arg("--output")
+        .arg(output)
+        .output()
+        .expect("failed to run the renderer");
+
+    assert!(result.status.success(), "renderer exited with {}: {}", result.status, String::from_utf8_lossy(&result.stderr));
+}
+
+fn read_png(path: &Path) -> Image {
+    let decoder = png::Decoder::new(File::open(path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e)));
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(info.color_type, png::ColorType::Rgb, "{} is not an RGB image", path.display());
+    pixels.truncate(info.buffer_size());
+
+    Image {
+        width: info.width as usize,
+        height: info.height as usize,
+        pixels,
+    }
+}
+
+fn write_png(path: &Path, image: &Image) {
+    let writer = BufWriter::new(File::create(path).unwrap());
+    let mut encoder = png::Encoder::new(writer, image.width as u32, image.height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header().unwrap().write_image_data(&image.pixels).unwrap();
+}
+
+// Compara dos imágenes y devuelve cuántos píxeles superan la tolerancia junto con
+// una imagen de diferencias: en rojo los píxeles distintos, el resto atenuado.
+fn compare(expected: &Image, actual: &Image) -> (usize, Image) {
+    let mut mismatched = 0;
+    let mut diff = Vec::with_capacity(expected.pixels.len());
+
+    for (e, a) in expected.pixels.chunks(3).zip(actual.pixels.chunks(3)) {
+        let differs = e.iter().zip(a).any(|(x, y)| x.abs_diff(*y) > CHANNEL_TOLERANCE);
+        if differs {
+            mismatched += 1;
+            diff.extend_from_slice(&[255, 0, 0]);
+        } else {
+            diff.extend(e.iter().map(|c| c / 4));
+        }
+    }
+
+    (mismatched, Image { width: expected.width, height: expected.height, pixels: diff })
+}
+
+fn check_shader(shader: &str) {
+    let actual_path = output_dir().join(format!("{}.png", shader));
+    render_shader(shader, &actual_path);
+    let actual = read_png(&actual_path);
+
+    let golden = golden_path(shader);
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all(golden.parent().unwrap()).unwrap();
+        fs::copy(&actual_path, &golden).unwrap();
+        return;
+    }
+
+    let expected = read_png(&golden);
+    assert_eq!(
+        (expected.width, expected.height),
+        (actual.width, actual.height),
+        "shader {}: image size changed",
+        shader
+    );
+
+    let (mismatched, diff) = compare(&expected, &actual);
+    let allowed = (MAX_MISMATCHED_FRACTION * (WIDTH * HEIGHT) as f32) as usize;
+    if mismatched > allowed {
+        let diff_path = output_dir().join(format!("{}_diff.png", shader));
+        write_png(&diff_path, &diff);
+        panic!(
+            "shader {}: {} pixels differ from {} (allowed {}), diff written to {}",
+            shader,
+            mismatched,
+            golden.display(),
+            allowed,
+            diff_path.display()
+        );
+    }
+}
+
+macro_rules! golden_test {
+    ($($name:ident),* $(,)?) => {
+        const TESTED_SHADERS: &[&str] = &[$(stringify!($name)),*];
+
+        $(
+            #[test]
+            fn $name() {
+                check_shader(stringify!($name));
+            }
+        )*
+    };
+}
+
+golden_test! {
+    lava_planet,
+    gas_planet,
+    sun,
+    rocky_planet,
+    gas_giant,
+    ice_planet,
+    wave,
+    moon,
+    atmospheric,
+    dynamic_surface,
+    clouds,
+    textured,
+    material,
+}
+
+// Cada shader registrado debe tener su prueba de imagen de referencia
+#[test]
+fn every_registered_shader_is_tested() {
+    let output = Command::new(env!("CARGO_BIN_EXE_Graficas_lab4"))
+        .current_dir(manifest_dir())
+        .arg("--list-shaders")
+        .output()
+        .expect("failed to run the renderer");
+    assert!(output.status.success());
+
+    let listed = String::from_utf8(output.stdout).unwrap();
+    let registered: Vec<&str> = listed.lines().collect();
+    assert_eq!(registered, TESTED_SHADERS);
+}
@@ -0,0 +1,122 @@
+// interpolation.rs
+
+mod common;
+
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+
+use graficas_lab4::{Fragment, Vertex};
+use graficas_lab4::triangle::{perspective_weights, rasterize, triangle, FragmentSink, Interpolation};
+use common::screen_vertex;
+
+// Triángulo rectángulo en pantalla cuyo vértice `b` está 4 veces más lejos
+// Vértice en pantalla con su w de clip space y la coordenada u (en `position` y `tex_coords`)
+fn vertex(x: f32, y: f32, w: f32, u: f32) -> Vertex {
+    let mut vertex = screen_vertex(x, y);
+    vertex.position = Vec3::new(u, 0.0, 0.0);
+    vertex.tex_coords = Vec2::new(u, 0.0);
+    vertex.clip_position = Vec4::new(0.0, 0.0, 0.0, w);
+    vertex
+}
+
+fn vertices() -> [Vertex; 3] {
+    [vertex(0.0, 0.0, 1.0, 0.0), vertex(64.0, 0.0, 4.0, 1.0), vertex(0.0, 64.0, 1.0, 0.0)]
+}
+
+#[test]
+fn perspective_weights_sum_to_one_and_match_affine_for_equal_w() {
+    let (p1, p2, p3) = perspective_weights(0.2, 0.3, 0.5, &Vec3::new(1.0, 0.25, 0.5));
+    assert!((p1 + p2 + p3 - 1.0).abs() < 1e-6);
+    assert!(p2 < 0.3);
+
+    let (p1, p2, p3) = perspective_weights(0.2, 0.3, 0.5, &Vec3::new(0.5, 0.5, 0.5));
+    assert!((p1 - 0.2).abs() < 1e-6 && (p2 - 0.3).abs() < 1e-6 && (p3 - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn attributes_are_perspective_correct() {
+    let [a, b, c] = vertices();
+    let fragments = triangle(&a, &b, &c, Interpolation::Perspective);
+    assert!(!fragments.is_empty());
+
+    for fragment in &fragments {
+        let w2 = (fragment.position.x + 0.5) / 64.0;
+        let expected = (w2 / 4.0) / (1.0 - w2 + w2 / 4.0);
+        assert!((fragment.tex_coords.x - expected).abs() < 1e-4);
+        assert!((fragment.vertex_position.x - expected).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn affine_mode_keeps_screen_space_weights() {
+    let [a, b, c] = vertices();
+    for fragment in triangle(&a, &b, &c, Interpolation::Affine) {
+        let w2 = (fragment.position.x + 0.5) / 64.0;
+        assert!((fragment.tex_coords.x - w2).abs() < 1e-4);
+    }
+}
+
+// La profundidad ya viene dividida entre w y se interpola igual en ambos modos
+#[test]
+fn depth_is_not_perspective_corrected() {
+    let [a, b, mut c] = vertices();
+    c.transformed_position.z = 1.0;
+    let perspective = triangle(&a, &b, &c, Interpolation::Perspective);
+    let affine = triangle(&a, &b, &c, Interpolation::Affine);
+    for (p, q) in perspective.iter().zip(&affine) {
+        assert_eq!(p.depth, q.depth);
+    }
+}
+
+// Las derivadas salen de los centros del bloque de 2x2 y son iguales para sus 4 pixeles
+#[test]
+fn derivatives_are_shared_by_each_quad() {
+    let [a, b, c] = vertices();
+    for fragment in triangle(&a, &b, &c, Interpolation::Affine) {
+        assert!((fragment.tex_coords_dx.x - 1.0 / 64.0).abs() < 1e-5);
+        assert!(fragment.tex_coords_dy.x.abs() < 1e-5);
+    }
+
+    let fragments = triangle(&a, &b, &c, Interpolation::Perspective);
+    let u = |x: f32| {
+        let w2 = (x + 0.5) / 64.0;
+        (w2 / 4.0) / (1.0 - w2 + w2 / 4.0)
+    };
+    for fragment in &fragments {
+        let quad_x = (fragment.position.x as i32 & !1) as f32;
+        assert!((fragment.tex_coords_dx.x - (u(quad_x + 1.0) - u(quad_x))).abs() < 1e-4);
+        assert!((fragment.vertex_position_dx.x - fragment.tex_coords_dx.x).abs() < 1e-5);
+
+        let neighbour = fragments.iter().find(|other| other.position == fragment.position + Vec2::new(1.0, 0.0));
+        if let Some(neighbour) = neighbour.filter(|_| fragment.position.x as i32 % 2 == 0) {
+            assert_eq!(neighbour.tex_coords_dx, fragment.tex_coords_dx);
+        }
+    }
+}
+
+// Sin derivadas: los mismos fragmentos, con tex_coords_dx y las demás en cero
+struct NoDerivatives(Vec<Fragment>);
+
+impl FragmentSink for NoDerivatives {
+    fn emit(&mut self, fragment: Fragment) {
+        self.0.push(fragment);
+    }
+
+    fn needs_derivatives(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn derivatives_are_skipped_when_the_sink_does_not_need_them() {
+    let [a, b, c] = vertices();
+    let mut sink = NoDerivatives(Vec::new());
+    rasterize(&a, &b, &c, Interpolation::Perspective, &mut sink);
+    let with_derivatives = triangle(&a, &b, &c, Interpolation::Perspective);
+
+    assert_eq!(sink.0.len(), with_derivatives.len());
+    for (fragment, expected) in sink.0.iter().zip(&with_derivatives) {
+        assert_eq!(fragment.tex_coords, expected.tex_coords);
+        assert_eq!(fragment.tex_coords_dx, Vec2::zeros());
+        assert_eq!(fragment.vertex_position_dy, Vec3::zeros());
+    }
+}
@@ -0,0 +1,202 @@
+// lighting.rs
+
+use nalgebra_glm::Vec3;
+use std::path::Path;
+use std::sync::Arc;
+
+use graficas_lab4::{Body, Camera, Color, Fragment, Light, LightKind, Obj, Orbit, PlanetShader, Renderer, Scene, ShaderRegistry, Uniforms};
+use graficas_lab4::light::{blinn_phong, diffuse_intensity, lambert};
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::scene_file::parse_scene;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-4
+}
+
+#[test]
+fn lambert_and_blinn_phong_terms() {
+    let normal = Vec3::new(0.0, 0.0, 1.0);
+    let sixty_degrees = Vec3::new(3.0f32.sqrt() / 2.0, 0.0, 0.5);
+
+    assert!(close(lambert(&normal, &normal), 1.0));
+    assert!(close(lambert(&normal, &sixty_degrees), 0.5));
+    assert_eq!(lambert(&normal, &-normal), 0.0);
+
+    // Con la luz y el ojo en la dirección de la normal el brillo es máximo
+    assert!(close(blinn_phong(&normal, &normal, &normal, 32.0), 1.0));
+    // El vector medio de luz a 60° y ojo sobre la normal está a 30°
+    let expected = 30.0f32.to_radians().cos().powf(8.0);
+    assert!(close(blinn_phong(&normal, &sixty_degrees, &normal, 8.0), expected));
+    // Sin brillos en el lado nocturno aunque el ojo mire de frente
+    assert_eq!(blinn_phong(&normal, &-sixty_degrees, &normal, 8.0), 0.0);
+}
+
+#[test]
+fn light_directions() {
+    let point = Light::point(Vec3::new(10.0, 0.0, 0.0), Color::from_float(1.0, 1.0, 1.0), 2.0);
+    assert_eq!(point.direction_from(&Vec3::new(4.0, 0.0, 0.0)), Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(point.radiance(), Color::from_float(2.0, 2.0, 2.0));
+
+    let sunlight = Light::directional(Vec3::new(0.0, -2.0, 0.0), Color::from_float(1.0, 1.0, 1.0), 1.0);
+    assert_eq!(sunlight.direction_from(&Vec3::new(5.0, 5.0, 5.0)), Vec3::new(0.0, 1.0, 0.0));
+
+    // La luz por defecto reproduce la iluminación fija anterior: dot(normal, +Z)
+    let normal = Vec3::new(0.6, 0.0, 0.8);
+    assert!(close(diffuse_intensity(&[Light::headlight()], &Vec3::zeros(), &normal, |_| 1.0), 0.8));
+}
+
+// Con una luz puntual a la derecha, el lado derecho de la esfera está iluminado y el izquierdo no
+#[test]
+fn the_day_side_faces_the_light() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mesh = Arc::new(Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array());
+    let mut scene = Scene::new();
+    scene.add(Body::new("planet", mesh, registry.create("rocky_planet").unwrap(), Arc::new(create_generic_noise()))).unwrap();
+    scene.add_light(Light::point(Vec3::new(20.0, 0.0, 0.0), Color::from_float(1.0, 1.0, 1.0), 1.0));
+
+    let mut renderer = Renderer::new(160, 120);
+    renderer.raster.threads = 1;
+    renderer.clear();
+    renderer.set_camera(&Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+    renderer.draw_scene(&scene);
+
+    let luminance = |x: usize| Color::from_hex(renderer.framebuffer.buffer[60 * 160 + x]).luminance();
+    let (left, right) = (luminance(70), luminance(90));
+    assert!(right > 0.01, "day side is dark: {}", right);
+    assert_eq!(left, 0.0, "night side is lit");
+    assert_eq!(renderer.uniforms.lights, scene.lights());
+}
+
+// El lado nocturno de estos planetas solo recibe `ambient` y el brillo atmosférico
+#[test]
+fn every_planet_shader_has_a_day_side() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mesh = Arc::new(Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array());
+    for shader in ["atmospheric", "dynamic_surface", "gas_planet", "gas_giant", "ice_planet"] {
+        let mut scene = Scene::new();
+        scene.add(Body::new("planet", Arc::clone(&mesh), registry.create(shader).unwrap(), Arc::new(create_generic_noise()))).unwrap();
+        scene.add_light(Light::point(Vec3::new(20.0, 0.0, 0.0), Color::from_float(1.0, 1.0, 1.0), 1.0));
+
+        let mut renderer = Renderer::new(160, 120);
+        renderer.raster.threads = 1;
+        renderer.clear();
+        renderer.set_camera(&Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+        renderer.draw_scene(&scene);
+
+        let luminance = |x: usize| Color::from_hex(renderer.framebuffer.buffer[60 * 160 + x]).luminance();
+        let (left, right) = (luminance(70), luminance(90));
+        assert!(right > 2.0 * left, "{}: day {} night {}", shader, right, left);
+    }
+}
+
+// Pinta `Fragment::intensity` en gris
+struct IntensityShader {
+    needs_intensity: bool,
+}
+
+impl PlanetShader for IntensityShader {
+    fn name(&self) -> &str {
+        "intensity"
+    }
+
+    fn fragment(&self, fragment: &Fragment, _uniforms: &Uniforms) -> Color {
+        Color::from_float(fragment.intensity, fragment.intensity, fragment.intensity)
+    }
+
+    fn needs_intensity(&self) -> bool {
+        self.needs_intensity
+    }
+}
+
+// Los shaders que no leen la intensidad no pagan las luces ni las sombras: les llega 1
+#[test]
+fn intensity_is_only_computed_when_the_shader_needs_it() {
+    let mesh = Arc::new(Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array());
+    let night_side = |needs_intensity: bool| {
+        let mut scene = Scene::new();
+        let shader = Box::new(IntensityShader { needs_intensity });
+        scene.add(Body::new("planet", Arc::clone(&mesh), shader, Arc::new(create_generic_noise()))).unwrap();
+        scene.add_light(Light::point(Vec3::new(20.0, 0.0, 0.0), Color::from_float(1.0, 1.0, 1.0), 1.0));
+
+        let mut renderer = Renderer::new(160, 120);
+        renderer.raster.threads = 1;
+        renderer.clear();
+        renderer.set_camera(&Camera::new(Vec3::new(0.0, 0.0, 5.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+        renderer.draw_scene(&scene);
+        renderer.framebuffer.buffer[60 * 160 + 70]
+    };
+
+    assert_eq!(night_side(true), 0x000000);
+    assert_eq!(night_side(false), 0xFFFFFF);
+}
+
+#[test]
+fn lights_follow_their_body() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mesh = Arc::new(Vec::new());
+    let noise = Arc::new(create_generic_noise());
+    let mut scene = Scene::new();
+    let center = scene.add(Body::new("center", Arc::clone(&mesh), registry.create("sun").unwrap(), Arc::clone(&noise))).unwrap();
+    let star = scene.add(
+        Body::new("star", mesh, registry.create("sun").unwrap(), noise).with_orbit(Orbit::circular(center, 5.0, 100.0)),
+    ).unwrap();
+    scene.add_light_on(star, Light::point(Vec3::zeros(), Color::from_float(1.0, 1.0, 1.0), 1.0)).unwrap();
+
+    for time in [0, 25, 60] {
+        scene.update(time);
+        let LightKind::Point { position } = scene.lights()[0].kind else {
+            panic!("attached lights are point lights");
+        };
+        assert_eq!(position, scene.bodies[star].position);
+    }
+}
+
+#[test]
+fn scene_files_declare_lights() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let source = r##"
+        [[body]]
+        name = "sun"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+        position = [1.0, 2.0, 3.0]
+
+        [[light]]
+        type = "point"
+        body = "sun"
+        color = [255, 244, 214]
+        intensity = 1.5
+
+        [[light]]
+        type = "directional"
+        direction = [0.0, -1.0, 0.0]
+        color = "#404080"
+    "##;
+    let scene = parse_scene(source, Path::new("lights.toml"), &registry).unwrap().scene;
+    let lights = scene.lights();
+
+    assert_eq!(lights.len(), 2);
+    assert_eq!(lights[0].kind, LightKind::Point { position: Vec3::new(1.0, 2.0, 3.0) });
+    assert_eq!(lights[0].color, Color::new(255, 244, 214));
+    assert_eq!(lights[0].intensity, 1.5);
+    assert_eq!(lights[1].kind, LightKind::Directional { direction: Vec3::new(0.0, -1.0, 0.0) });
+    assert_eq!(lights[1].intensity, 1.0);
+}
+
+#[test]
+fn invalid_lights_are_reported() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let cases = [
+        ("type = \"point\"", "light #1: point lights need either a position or a body"),
+        ("type = \"point\"\nbody = \"vulcan\"", "light #1: no body named 'vulcan' to attach the light to"),
+        ("type = \"directional\"", "light #1: directional lights need a direction"),
+        ("type = \"directional\"\ndirection = [0.0, 0.0, 0.0]", "light #1: light direction must not be zero"),
+        ("type = \"directional\"\ndirection = [0.0, 0.0, 1.0]\nbody = \"sun\"", "light #1: directional lights only take a direction"),
+    ];
+
+    for (light, expected) in cases {
+        let source = format!("[[body]]\nname = \"sun\"\nmesh = \"assets/model/sphere.obj\"\nshader = \"sun\"\n\n[[light]]\n{}\n", light);
+        let error = parse_scene(&source, Path::new("lights.toml"), &registry).err().expect(light).to_string();
+        assert_eq!(error, format!("lights.toml: {}", expected));
+    }
+}
@@ -0,0 +1,152 @@
+// material.rs
+
+use nalgebra_glm::Vec3;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+use graficas_lab4::{Body, Camera, CullMode, FrontFace, Obj, Renderer, Scene, ShaderRegistry};
+use graficas_lab4::framebuffer::Framebuffer;
+use graficas_lab4::material::NO_MATERIAL;
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::scene_file::parse_scene;
+
+#[test]
+fn obj_models_keep_their_mtl_materials() {
+    let obj = Obj::load("assets/model/ship6.obj").unwrap();
+    assert!(obj.warnings().is_empty(), "{:?}", obj.warnings());
+
+    let names: Vec<&str> = obj.materials().iter().map(|material| material.name.as_str()).collect();
+    assert_eq!(names.len(), 4);
+    for name in ["Material.002", "Material.008", "Material.009", "Material.010"] {
+        assert!(names.contains(&name), "{:?}", names);
+    }
+    let material = &obj.materials()[0];
+    assert!((material.diffuse.r() - 0.64).abs() < 1e-6);
+    assert!((material.specular.g() - 0.5).abs() < 1e-6);
+    assert!((material.shininess - 96.08).abs() < 1e-2);
+    assert!(material.emissive.is_black());
+    assert_eq!(material.opacity, 1.0);
+
+    // Cada malla lleva el índice de su material en los vértices
+    let used: HashSet<u32> = obj.get_vertex_array().iter().map(|vertex| vertex.material).collect();
+    assert_eq!(used.len(), 4);
+    assert!(used.iter().all(|&index| (index as usize) < names.len()));
+}
+
+#[test]
+fn missing_material_libraries_are_a_warning() {
+    let obj = Obj::load("assets/model/sphere.obj").unwrap();
+    assert!(obj.materials().is_empty());
+    assert_eq!(obj.warnings().len(), 1);
+    assert!(obj.warnings()[0].contains("esferatriangulada.mtl"), "{}", obj.warnings()[0]);
+    assert!(obj.get_vertex_array().iter().all(|vertex| vertex.material == NO_MATERIAL));
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graficas_material_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Cuadrado de 2x2 en el plano z = 0 mirando hacia +Z, con el material `glow`
+fn write_quad(dir: &Path, mtl: &str) -> String {
+    fs::write(dir.join("quad.mtl"), mtl).unwrap();
+    let obj = "mtllib quad.mtl\n\
+        v -1 -1 0\nv 1 -1 0\nv 1 1 0\nv -1 1 0\n\
+        vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+        vn 0 0 1\n\
+        usemtl glow\n\
+        f 1/1/1 2/2/1 3/3/1\nf 1/1/1 3/3/1 4/4/1\n";
+    let path = dir.join("quad.obj");
+    fs::write(&path, obj).unwrap();
+    path.to_str().unwrap().to_string()
+}
+
+#[test]
+fn mtl_files_declare_emission_opacity_and_maps() {
+    let dir = temp_dir("maps");
+    let mut image = Framebuffer::new(2, 2);
+    image.buffer = vec![0x00FF00; 4];
+    image.save(dir.join("green.png")).unwrap();
+
+    let path = write_quad(&dir, "newmtl glow\nKd 1 1 1\nKe 1 0 0\nd 0.5\nmap_Kd green.png\n\nnewmtl broken\nmap_Kd missing.png\n");
+    let obj = Obj::load(&path).unwrap();
+    let glow = &obj.materials()[0];
+    assert_eq!(glow.emissive.to_hex(), 0xFF0000);
+    assert_eq!(glow.opacity, 0.5);
+    assert_eq!(glow.diffuse_map.as_ref().unwrap().texel(1, 1).to_hex(), 0x00FF00);
+
+    // Un mapa que falta deja el material sin él y un aviso
+    assert!(obj.materials()[1].diffuse_map.is_none());
+    assert_eq!(obj.warnings().len(), 1);
+    assert!(obj.warnings()[0].contains("material 'broken'"), "{}", obj.warnings()[0]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn the_material_shader_uses_the_mesh_materials() {
+    let dir = temp_dir("shader");
+    let path = write_quad(&dir, "newmtl glow\nKd 0 0 0\nKs 0 0 0\nKe 0 0 1\n");
+    let obj = Obj::load(&path).unwrap();
+    let registry = ShaderRegistry::with_builtin_shaders();
+
+    let render = |materials| {
+        let body = Body::new("quad", Arc::new(obj.get_vertex_array()), registry.create("material").unwrap(), Arc::new(create_generic_noise()))
+            .with_materials(materials)
+            .with_culling(CullMode::None, FrontFace::default());
+        let mut scene = Scene::new();
+        scene.add(body).unwrap();
+
+        let mut renderer = Renderer::new(40, 30);
+        renderer.raster.threads = 1;
+        renderer.clear();
+        renderer.set_camera(&Camera::new(Vec3::new(0.0, 0.0, 3.0), Vec3::zeros(), Vec3::new(0.0, 1.0, 0.0)));
+        renderer.draw_scene(&scene);
+        renderer.framebuffer.buffer[15 * 40 + 20]
+    };
+
+    // Solo emite azul; sin materiales se usa el gris por defecto
+    assert_eq!(render(Arc::new(obj.materials().to_vec())), 0x0000FF);
+    let plain = render(Arc::new(Vec::new()));
+    assert!(plain != 0x0000FF && (plain >> 16) & 0xFF == plain & 0xFF, "{:06X}", plain);
+
+    // Los archivos de escena pasan los materiales al cuerpo
+    let source = format!("[[body]]\nname = \"quad\"\nshader = \"material\"\nmesh = \"{}\"\n", path);
+    let loaded = parse_scene(&source, Path::new("quad.toml"), &registry).unwrap();
+    assert_eq!(loaded.scene.bodies[0].materials.len(), 1);
+    assert!(loaded.warnings.is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn scene_files_report_mesh_warnings_once() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let source = "[[body]]\nname = \"a\"\nmesh = \"assets/model/sphere.obj\"\nshader = \"material\"\n\n[[body]]\nname = \"b\"\nmesh = \"assets/model/sphere.obj\"\nshader = \"material\"\nposition = [3.0, 0.0, 0.0]\n";
+    let loaded = parse_scene(source, Path::new("spheres.toml"), &registry).unwrap();
+    assert_eq!(loaded.warnings.len(), 1);
+    assert!(loaded.warnings[0].contains("esferatriangulada.mtl"));
+}
+
+// Con --shader o con una escena, el aviso del .mtl que falta aparece una sola vez
+#[test]
+fn the_viewer_prints_mesh_warnings_in_both_modes() {
+    let dir = temp_dir("viewer");
+    for mode in [&["--shader", "sun"][..], &[]] {
+        let output = Command::new(env!("CARGO_BIN_EXE_Graficas_lab4"))
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .args(["--headless", "--no-sky", "--width", "8", "--height", "8"])
+            .args(mode)
+            .arg("--output")
+            .arg(dir.join("frame.png"))
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert_eq!(stderr.matches("warning:").count(), 1, "{:?}: {}", mode, stderr);
+        assert!(stderr.contains("esferatriangulada.mtl"), "{}", stderr);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
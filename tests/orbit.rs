@@ -0,0 +1,96 @@
+// orbit.rs
+
+use nalgebra_glm::Vec3;
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use graficas_lab4::{Body, Color, HierarchyError, Light, Orbit, Scene, ShaderRegistry};
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::orbit::solve_kepler;
+
+fn assert_close(actual: f32, expected: f32) {
+    assert!((actual - expected).abs() < 1e-3, "expected {}, got {}", expected, actual);
+}
+
+fn body(name: &str) -> Body {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    Body::new(name, Arc::new(Vec::new()), registry.create("moon").unwrap(), Arc::new(create_generic_noise()))
+}
+
+#[test]
+fn kepler_solution_satisfies_the_equation() {
+    for &e in &[0.0, 0.1, 0.5, 0.9] {
+        for step in 0..16 {
+            let mean_anomaly = step as f32 * 2.0 * PI / 16.0;
+            let eccentric_anomaly = solve_kepler(mean_anomaly, e);
+            assert_close(eccentric_anomaly - e * eccentric_anomaly.sin(), mean_anomaly);
+        }
+    }
+}
+
+#[test]
+fn circular_orbit_keeps_its_radius() {
+    let orbit = Orbit::circular(0, 3.0, 100.0);
+    for time in 0..100 {
+        assert_close(orbit.position(time as f32).magnitude(), 3.0);
+    }
+}
+
+#[test]
+fn eccentric_orbit_reaches_periapsis_and_apoapsis() {
+    let orbit = Orbit::circular(0, 4.0, 200.0).with_eccentricity(0.5);
+    assert_close(orbit.position(0.0).magnitude(), 2.0);
+    assert_close(orbit.position(100.0).magnitude(), 6.0);
+}
+
+#[test]
+fn orbit_repeats_after_one_period() {
+    let orbit = Orbit::circular(0, 2.0, 50.0).with_eccentricity(0.3).with_phase(1.0);
+    let start = orbit.position(10.0);
+    let later = orbit.position(60.0);
+    assert_close((start - later).magnitude(), 0.0);
+}
+
+#[test]
+fn inclination_lifts_the_orbit_out_of_the_xz_plane() {
+    let orbit = Orbit::circular(0, 1.0, 100.0).with_inclination(PI / 2.0);
+    let quarter = orbit.position(25.0);
+    assert_close(quarter.y, 1.0);
+    assert_close(quarter.z, 0.0);
+}
+
+#[test]
+fn moons_follow_their_planet() {
+    let mut scene = Scene::new();
+    let sun = scene.add(body("sun").with_position(Vec3::new(1.0, 0.0, 0.0))).unwrap();
+    let planet = scene.add(body("planet").with_orbit(Orbit::circular(sun, 5.0, 100.0))).unwrap();
+    let moon = scene.add(body("moon").with_orbit(Orbit::circular(planet, 1.0, 10.0))).unwrap();
+
+    scene.update(25);
+
+    let planet_position = scene.bodies[planet].position;
+    assert_close((planet_position - Vec3::new(1.0, 0.0, 5.0)).magnitude(), 0.0);
+    assert_close((scene.bodies[moon].position - planet_position).magnitude(), 1.0);
+}
+
+#[test]
+fn spin_advances_with_time() {
+    let mut scene = Scene::new();
+    scene.add(body("planet").with_spin(0.4, 100.0)).unwrap();
+
+    scene.update(25);
+    assert_close(scene.bodies[0].spin_angle, PI / 2.0);
+    assert_close(scene.bodies[0].axial_tilt, 0.4);
+}
+
+#[test]
+fn parent_must_be_added_first() {
+    let mut scene = Scene::new();
+    let error = scene.add(body("moon").with_orbit(Orbit::circular(3, 1.0, 10.0))).unwrap_err();
+    assert_eq!(error, HierarchyError::OrbitParent { body: "moon".to_string(), parent: 3 });
+    assert!(scene.bodies.is_empty());
+
+    let light = Light::point(Vec3::zeros(), Color::from_float(1.0, 1.0, 1.0), 1.0);
+    assert_eq!(scene.add_light_on(0, light).unwrap_err().to_string(), "light attached to body 0, which has not been added yet");
+    assert!(scene.lights.is_empty());
+}
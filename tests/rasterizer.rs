@@ -0,0 +1,145 @@
+// rasterizer.rs
+
+mod common;
+
+use nalgebra_glm::Vec3;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::{self, ThreadId};
+
+use graficas_lab4::{Camera, Color, Fragment, PlanetShader, Renderer, ShaderRegistry, Uniforms, Vertex};
+use graficas_lab4::scene_file::load_scene;
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::triangle::{rasterize, triangle, Interpolation};
+use common::{camera_on_z, sphere};
+
+// Cuenta cuántas veces se llama al fragment shader
+#[derive(Default)]
+struct CountingShader {
+    calls: AtomicUsize,
+}
+
+impl PlanetShader for CountingShader {
+    fn name(&self) -> &str {
+        "counting"
+    }
+
+    fn fragment(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> Color {
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        Color::new(255, 255, 255)
+    }
+}
+
+fn renderer() -> Renderer {
+    let mut renderer = Renderer::new(160, 120);
+    renderer.clear();
+    renderer.set_camera(&camera_on_z(5.0));
+    renderer
+}
+
+#[test]
+fn streaming_matches_collected_fragments() {
+    let mut a = Vertex::new(Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0), Default::default());
+    let mut b = a.clone();
+    let mut c = a.clone();
+    a.transformed_position = Vec3::new(2.0, 3.0, 0.1);
+    b.transformed_position = Vec3::new(40.0, 8.0, 0.5);
+    c.transformed_position = Vec3::new(12.0, 30.0, 0.9);
+
+    let collected = triangle(&a, &b, &c, Interpolation::Perspective);
+    let mut streamed = Vec::new();
+    rasterize(&a, &b, &c, Interpolation::Perspective, &mut |fragment: Fragment| streamed.push((fragment.position, fragment.depth)));
+
+    assert!(!collected.is_empty());
+    let collected: Vec<_> = collected.iter().map(|fragment| (fragment.position, fragment.depth)).collect();
+    assert_eq!(collected, streamed);
+}
+
+// Los fragmentos tapados por lo que ya está en el zbuffer no llegan al shader
+#[test]
+fn hidden_fragments_are_not_shaded() {
+    let mesh = sphere();
+    let noise = Arc::new(create_generic_noise());
+    let mut renderer = renderer();
+
+    let near = create_model_matrix(Vec3::new(0.0, 0.0, 1.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+    let far = create_model_matrix(Vec3::new(0.0, 0.0, -1.0), 0.8, Vec3::new(0.0, 0.0, 0.0));
+
+    let first = CountingShader::default();
+    renderer.draw(&mesh, near, &noise, &first);
+    assert!(first.calls.load(Ordering::Relaxed) > 0);
+
+    let hidden = CountingShader::default();
+    renderer.draw(&mesh, far, &noise, &hidden);
+    assert_eq!(hidden.calls.load(Ordering::Relaxed), 0);
+
+    let again = CountingShader::default();
+    renderer.draw(&mesh, near, &noise, &again);
+    assert_eq!(again.calls.load(Ordering::Relaxed), 0);
+}
+
+fn render_solar_system(threads: usize, eye: Vec3) -> Renderer {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mut scene = load_scene("assets/scenes/solar_system.toml", &registry).unwrap().scene;
+    scene.update(42);
+
+    let mut renderer = Renderer::new(203, 157);
+    renderer.raster.threads = threads;
+    renderer.set_time(42);
+    renderer.clear();
+    renderer.set_camera(&Camera::new(eye, Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+    renderer.draw_scene(&scene);
+    renderer
+}
+
+// Los tiles en paralelo producen exactamente la misma imagen que un solo hilo
+#[test]
+fn tiled_rendering_matches_single_threaded() {
+    for eye in [Vec3::new(0.0, 8.0, 18.0), Vec3::new(0.5, 0.3, 2.6)] {
+        let single = render_solar_system(1, eye);
+        for threads in [2, 3, 8] {
+            let tiled = render_solar_system(threads, eye);
+            assert!(tiled.framebuffer.buffer == single.framebuffer.buffer, "{} threads", threads);
+            assert!(tiled.framebuffer.zbuffer == single.framebuffer.zbuffer, "{} threads", threads);
+        }
+    }
+}
+
+// Guarda los hilos en los que se llamó al fragment shader
+#[derive(Default)]
+struct ThreadShader {
+    threads: Mutex<HashSet<ThreadId>>,
+}
+
+impl PlanetShader for ThreadShader {
+    fn name(&self) -> &str {
+        "threads"
+    }
+
+    fn fragment(&self, _fragment: &Fragment, _uniforms: &Uniforms) -> Color {
+        self.threads.lock().unwrap().insert(thread::current().id());
+        Color::new(255, 255, 255)
+    }
+}
+
+// Pocos triángulos o un solo tile se sombrean en el hilo actual, sin crear hilos
+#[test]
+fn small_draws_skip_the_tiled_path() {
+    let mesh = sphere();
+    let noise = Arc::new(create_generic_noise());
+    let draw = |mesh: &[Vertex], scale: f32| {
+        let mut renderer = renderer();
+        renderer.raster.threads = 4;
+        let shader = ThreadShader::default();
+        renderer.draw(mesh, create_model_matrix(Vec3::zeros(), scale, Vec3::zeros()), &noise, &shader);
+        shader.threads.into_inner().unwrap()
+    };
+
+    let current = HashSet::from([thread::current().id()]);
+    let facing: Vec<Vertex> = mesh.chunks_exact(3).filter(|tri| tri[0].normal.z > 0.5).take(20).flatten().cloned().collect();
+    assert_eq!(draw(&facing, 3.0), current);
+    assert_eq!(draw(&mesh, 0.1), current);
+    assert!(!draw(&mesh, 3.0).contains(&thread::current().id()));
+}
@@ -0,0 +1,360 @@
+// scene_file.rs
+
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::{self, Command};
+
+use graficas_lab4::{ParameterError, ShaderRegistry};
+use graficas_lab4::scene_file::{
+    load_parameters, load_scene, parse_parameters, parse_scene, LoadedScene, ParameterSection, SceneError,
+};
+
+fn parse(source: &str) -> Result<LoadedScene, SceneError> {
+    parse_scene(source, Path::new("test.toml"), &ShaderRegistry::with_builtin_shaders())
+}
+
+fn error_message(source: &str) -> String {
+    match parse(source) {
+        Ok(_) => panic!("scene should have been rejected"),
+        Err(error) => error.to_string(),
+    }
+}
+
+#[test]
+fn default_scene_loads() {
+    let loaded = load_scene("assets/scenes/solar_system.toml", &ShaderRegistry::with_builtin_shaders()).unwrap();
+    assert!(loaded.camera.is_some());
+    assert_eq!(loaded.scene.bodies[0].name, "sun");
+
+    let luna = loaded.scene.bodies.iter().find(|body| body.name == "luna").unwrap();
+    let gaia = loaded.scene.bodies.iter().position(|body| body.name == "gaia").unwrap();
+    assert_eq!(luna.orbit.unwrap().parent, gaia);
+}
+
+#[test]
+fn bodies_take_shader_parameters_and_noise_settings() {
+    let loaded = parse(r##"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        scale = 0.5
+        parameters = { base_color = "#102030", crater_zoom = 4 }
+        noise = { seed = 7, type = "cellular", fractal_type = "fbm", octaves = 3, frequency = 0.1 }
+    "##).unwrap();
+
+    let body = &loaded.scene.bodies[0];
+    assert_eq!(body.scale, 0.5);
+    let parameters = body.shader.parameters();
+    let base_color = parameters.iter().find(|p| p.name == "base_color").unwrap();
+    assert_eq!(base_color.value.as_color("base_color").unwrap().to_hex(), 0x102030);
+    let zoom = parameters.iter().find(|p| p.name == "crater_zoom").unwrap();
+    assert_eq!(format!("{:?}", zoom.value), "Float(4.0)");
+}
+
+#[test]
+fn unknown_shader_is_reported_with_the_body_name() {
+    let message = error_message(r#"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky"
+    "#);
+    assert!(message.starts_with("test.toml: body 'terra': unknown shader 'rocky'"), "{}", message);
+}
+
+#[test]
+fn unknown_parameter_is_rejected() {
+    let message = error_message(r#"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        parameters = { colour = [1, 2, 3] }
+    "#);
+    assert!(message.contains("no parameter named 'colour'"), "{}", message);
+}
+
+#[test]
+fn parameter_type_is_checked() {
+    let message = error_message(r#"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        parameters = { base_color = 0.5 }
+    "#);
+    assert!(message.contains("'base_color' expects a color"), "{}", message);
+
+    let message = error_message(r#"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        parameters = { base_color = [300, 0, 0] }
+    "#);
+    assert!(message.contains("0 to 255"), "{}", message);
+}
+
+#[test]
+fn unknown_noise_preset_lists_the_available_ones() {
+    let message = error_message(r#"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        noise = "granite"
+    "#);
+    assert!(message.contains("unknown noise preset 'granite'"), "{}", message);
+    assert!(message.contains("lava"), "{}", message);
+}
+
+#[test]
+fn orbit_parent_must_come_first() {
+    let message = error_message(r#"
+        [[body]]
+        name = "luna"
+        mesh = "assets/model/sphere.obj"
+        shader = "moon"
+        orbit = { parent = "gaia", semi_major_axis = 1.0, period = 10.0 }
+
+        [[body]]
+        name = "gaia"
+        mesh = "assets/model/sphere.obj"
+        shader = "dynamic_surface"
+    "#);
+    assert!(message.contains("orbit parent 'gaia' must be declared before"), "{}", message);
+}
+
+#[test]
+fn eccentricity_must_describe_a_closed_orbit() {
+    let message = error_message(r#"
+        [[body]]
+        name = "sun"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+
+        [[body]]
+        name = "comet"
+        mesh = "assets/model/sphere.obj"
+        shader = "moon"
+        orbit = { parent = "sun", semi_major_axis = 1.0, eccentricity = 1.5, period = 10.0 }
+    "#);
+    assert!(message.contains("eccentricity"), "{}", message);
+}
+
+#[test]
+fn unknown_fields_and_duplicate_names_are_errors() {
+    let message = error_message(r#"
+        [[body]]
+        name = "sun"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+        size = 2.0
+    "#);
+    assert!(message.contains("unknown field `size`"), "{}", message);
+
+    let message = error_message(r#"
+        [[body]]
+        name = "sun"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+
+        [[body]]
+        name = "sun"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+    "#);
+    assert!(message.contains("already declared"), "{}", message);
+}
+
+#[test]
+fn missing_mesh_is_reported() {
+    let message = error_message(r#"
+        [[body]]
+        name = "ship"
+        shader = "moon"
+        mesh = "assets/model/missing.obj"
+    "#);
+    assert!(message.contains("failed to load mesh 'assets/model/missing.obj'"), "{}", message);
+
+    // Todo cuerpo declara su malla; no hay una por defecto
+    let message = error_message("[[body]]\nname = \"ship\"\nshader = \"moon\"\n");
+    assert!(message.contains("missing field `mesh`"), "{}", message);
+}
+
+#[test]
+fn relative_paths_start_at_the_scene_file() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let source = "[[body]]\nname = \"ship\"\nshader = \"material\"\nmesh = \"../model/ship6.obj\"\n";
+    let loaded = parse_scene(source, Path::new("assets/scenes/ships.toml"), &registry).unwrap();
+    assert_eq!(loaded.scene.bodies[0].materials.len(), 4);
+
+    // La misma ruta ya no se busca desde el directorio actual
+    let message = parse_scene(source, Path::new("ships.toml"), &registry).err().unwrap().to_string();
+    assert!(message.contains("failed to load mesh '../model/ship6.obj'"), "{}", message);
+}
+
+// Fuera del proyecto la malla del modo --shader se indica con --mesh
+#[test]
+fn the_shader_mode_mesh_comes_from_the_command_line() {
+    let dir = env::temp_dir().join(format!("graficas_lab4_mesh_option_{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let run = |extra: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_Graficas_lab4"))
+            .current_dir(&dir)
+            .args(["--headless", "--no-sky", "--width", "8", "--height", "8", "--shader", "sun", "--output", "frame.png"])
+            .args(extra)
+            .output()
+            .unwrap()
+    };
+
+    let output = run(&[]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("assets/model/sphere.obj: failed to load mesh"), "{}", stderr);
+
+    let mesh = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/model/sphere.obj");
+    let output = run(&["--mesh", mesh.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(dir.join("frame.png").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+fn float_parameter(loaded: &LoadedScene, body: &str, name: &str) -> String {
+    let body = loaded.scene.bodies.iter().find(|b| b.name == body).unwrap();
+    let parameters = body.shader.parameters();
+    format!("{:?}", parameters.iter().find(|p| p.name == name).unwrap().value)
+}
+
+const TWO_ROCKS: &str = r#"
+    [[body]]
+    name = "terra"
+    mesh = "assets/model/sphere.obj"
+    shader = "rocky_planet"
+
+    [[body]]
+    name = "ares"
+    mesh = "assets/model/sphere.obj"
+    shader = "rocky_planet"
+"#;
+
+#[test]
+fn parameter_files_apply_per_shader_then_per_body() {
+    let mut loaded = parse(TWO_ROCKS).unwrap();
+    let parameters = parse_parameters(r#"
+        [shader.rocky_planet]
+        crater_zoom = 3.0
+
+        [body.ares]
+        crater_zoom = 7.0
+    "#, Path::new("params.toml")).unwrap();
+
+    parameters.apply(&mut loaded.scene).unwrap();
+    assert_eq!(float_parameter(&loaded, "terra", "crater_zoom"), "Float(3.0)");
+    assert_eq!(float_parameter(&loaded, "ares", "crater_zoom"), "Float(7.0)");
+}
+
+#[test]
+fn removed_parameters_return_to_the_scene_values() {
+    let mut loaded = parse(r#"
+        [[body]]
+        name = "terra"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        parameters = { crater_zoom = 4 }
+    "#).unwrap();
+    let parameters = parse_parameters("[body.terra]\ncrater_zoom = 7.0\nbase_color = \"#ff0000\"\n", Path::new("params.toml")).unwrap();
+    parameters.apply(&mut loaded.scene).unwrap();
+    assert_eq!(float_parameter(&loaded, "terra", "crater_zoom"), "Float(7.0)");
+
+    parse_parameters("", Path::new("params.toml")).unwrap().apply(&mut loaded.scene).unwrap();
+    assert_eq!(float_parameter(&loaded, "terra", "crater_zoom"), "Float(4.0)");
+    let defaults = ShaderRegistry::with_builtin_shaders().create("rocky_planet").unwrap().parameters();
+    let body = &loaded.scene.bodies[0];
+    let base_color = body.shader.parameters().into_iter().find(|p| p.name == "base_color").unwrap();
+    let default_color = defaults.into_iter().find(|p| p.name == "base_color").unwrap();
+    assert_eq!(format!("{:?}", base_color.value), format!("{:?}", default_color.value));
+}
+
+#[test]
+fn invalid_parameter_files_leave_the_scene_untouched() {
+    let mut loaded = parse(TWO_ROCKS).unwrap();
+    let parameters = parse_parameters(r#"
+        [shader.rocky_planet]
+        crater_zoom = 3.0
+
+        [body.ares]
+        base_color = 2.0
+    "#, Path::new("params.toml")).unwrap();
+
+    let error = parameters.apply(&mut loaded.scene).unwrap_err();
+    assert!(matches!(
+        &error,
+        SceneError::Parameter { section: ParameterSection::Body(body), source: ParameterError::WrongType { .. }, .. } if body == "ares"
+    ));
+    assert_eq!(error.to_string(), "params.toml: body 'ares': parameter 'base_color' expects a color value");
+    assert_eq!(float_parameter(&loaded, "terra", "crater_zoom"), "Float(10.0)");
+
+    // Un valor inválido de una sección [shader.*] se reporta en esa sección
+    let parameters = parse_parameters("[shader.rocky_planet]\nbase_color = 2.0\n", Path::new("params.toml")).unwrap();
+    let message = parameters.apply(&mut loaded.scene).unwrap_err().to_string();
+    assert_eq!(message, "params.toml: shader 'rocky_planet': parameter 'base_color' expects a color value");
+
+    let parameters = parse_parameters("[body.phobos]\nzoom = 1.0\n", Path::new("params.toml")).unwrap();
+    let message = parameters.apply(&mut loaded.scene).unwrap_err().to_string();
+    assert!(message.contains("body 'phobos': no body with this name"), "{}", message);
+}
+
+#[test]
+fn parameter_errors_tell_syntax_from_rejected_values() {
+    let mut loaded = parse(TWO_ROCKS).unwrap();
+    let error = parse_parameters("[body.terra\ncrater_zoom = 3.0\n", Path::new("params.toml")).err().unwrap();
+    assert!(matches!(error, SceneError::Parse { .. }), "{}", error);
+
+    let parameters = parse_parameters("[body.terra]\nbase_color = \"#12\"\n", Path::new("params.toml")).unwrap();
+    let error = parameters.apply(&mut loaded.scene).unwrap_err();
+    assert!(matches!(error, SceneError::Parameter { source: ParameterError::Invalid { .. }, .. }), "{}", error);
+    assert_eq!(error.to_string(), "params.toml: body 'terra': parameter 'base_color': '#12' is not a #RRGGBB or #RRGGBBAA color");
+
+    let parameters = parse_parameters("[shader.gas_giant]\nzoom = 1.0\n", Path::new("params.toml")).unwrap();
+    let error = parameters.apply(&mut loaded.scene).unwrap_err();
+    assert!(matches!(error, SceneError::UnmatchedSection { section: ParameterSection::Shader(_), .. }), "{}", error);
+}
+
+#[test]
+fn shader_sections_must_match_a_body() {
+    let mut loaded = parse(TWO_ROCKS).unwrap();
+    for shader in ["rocky", "gas_giant"] {
+        let source = format!("[shader.{}]\nzoom = 1.0\n", shader);
+        let parameters = parse_parameters(&source, Path::new("params.toml")).unwrap();
+        let message = parameters.apply(&mut loaded.scene).unwrap_err().to_string();
+        assert_eq!(message, format!("params.toml: shader '{}': no body in the scene uses this shader", shader));
+    }
+}
+
+#[test]
+fn parameter_files_survive_switching_a_body_shader() {
+    let mut loaded = parse(TWO_ROCKS).unwrap();
+    let gas_giant = ShaderRegistry::with_builtin_shaders().create("gas_giant").unwrap();
+    loaded.scene.bodies[0].set_shader(gas_giant);
+    let parameters = parse_parameters(r#"
+        [shader.rocky_planet]
+        crater_zoom = 3.0
+
+        [body.terra]
+        crater_zoom = 7.0
+    "#, Path::new("params.toml")).unwrap();
+
+    parameters.apply(&mut loaded.scene).unwrap();
+    assert_eq!(loaded.scene.bodies[0].declared_shader(), "rocky_planet");
+    assert_eq!(float_parameter(&loaded, "ares", "crater_zoom"), "Float(3.0)");
+}
+
+#[test]
+fn example_parameter_file_matches_the_default_scene() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mut loaded = load_scene("assets/scenes/solar_system.toml", &registry).unwrap();
+    load_parameters("assets/scenes/parameters.toml").unwrap().apply(&mut loaded.scene).unwrap();
+}
@@ -0,0 +1,102 @@
+// shadow.rs
+
+use nalgebra_glm::Vec3;
+use std::path::Path;
+
+use graficas_lab4::{Camera, CullMode, FrontFace, Obj, Renderer, ShaderRegistry, ShadowSettings};
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::scene_file::parse_scene;
+use graficas_lab4::shadow::{CubeShadowMap, ShadowCaster};
+
+// Esfera de radio ~0.52 a 3 unidades de la luz, sobre el eje +X
+fn occluded_light(settings: &ShadowSettings) -> CubeShadowMap {
+    let mesh = Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array();
+    let caster = ShadowCaster {
+        mesh: &mesh,
+        model_matrix: create_model_matrix(Vec3::new(3.0, 0.0, 0.0), 1.0, Vec3::zeros()),
+        cull_mode: CullMode::Back,
+        front_face: FrontFace::CounterClockwise,
+    };
+    let mut map = CubeShadowMap::new(Vec3::zeros(), settings);
+    map.render(&[caster], 1);
+    map
+}
+
+#[test]
+fn points_behind_a_caster_are_in_shadow() {
+    let settings = ShadowSettings { size: 128, ..ShadowSettings::default() };
+    let map = occluded_light(&settings);
+    let visibility = |x: f32, y: f32, z: f32| map.visibility(&Vec3::new(x, y, z), settings.bias, 0);
+
+    assert_eq!(visibility(6.0, 0.0, 0.0), 0.0);
+    assert_eq!(visibility(6.0, 0.3, -0.2), 0.0);
+    // Entre la luz y el objeto, y en las otras caras del cubo, no hay sombra
+    assert_eq!(visibility(1.5, 0.0, 0.0), 1.0);
+    assert_eq!(visibility(-6.0, 0.0, 0.0), 1.0);
+    for direction in [Vec3::y(), -Vec3::y(), Vec3::z(), -Vec3::z()] {
+        assert_eq!(map.visibility(&(direction * 6.0), settings.bias, 0), 1.0);
+    }
+}
+
+// Con PCF el borde de la sombra tiene valores intermedios; sin PCF solo 0 o 1
+#[test]
+fn pcf_softens_the_shadow_edge() {
+    let settings = ShadowSettings { size: 128, ..ShadowSettings::default() };
+    let map = occluded_light(&settings);
+    let edge: Vec<Vec3> = (0..60).map(|i| Vec3::new(6.0, 0.8 + i as f32 * 0.01, 0.0)).collect();
+
+    assert!(edge.iter().all(|p| matches!(map.visibility(p, settings.bias, 0), v if v == 0.0 || v == 1.0)));
+    let partial = edge.iter().map(|p| map.visibility(p, settings.bias, 2)).filter(|v| *v > 0.0 && *v < 1.0).count();
+    assert!(partial >= 2, "only {} partially lit samples", partial);
+}
+
+fn render_eclipse(extra: &str, shadows: bool) -> Vec<u32> {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let source = format!(
+        r#"
+        [[light]]
+        type = "point"
+        body = "sun"
+
+        [[body]]
+        name = "sun"
+        mesh = "assets/model/sphere.obj"
+        shader = "sun"
+
+        [[body]]
+        name = "planet"
+        mesh = "assets/model/sphere.obj"
+        shader = "rocky_planet"
+        position = [5.0, 0.0, 0.0]
+
+        [[body]]
+        name = "moon"
+        mesh = "assets/model/sphere.obj"
+        shader = "moon"
+        position = [3.5, 0.0, 0.15]
+        scale = 0.35
+        {}
+        "#,
+        extra
+    );
+    let scene = parse_scene(&source, Path::new("eclipse.toml"), &registry).unwrap().scene;
+
+    let mut renderer = Renderer::new(120, 90);
+    renderer.raster.threads = 1;
+    renderer.uniforms.shadow_settings.enabled = shadows;
+    renderer.clear();
+    renderer.set_camera(&Camera::new(Vec3::new(2.5, 1.0, 3.0), Vec3::new(5.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+    renderer.draw_scene(&scene);
+    renderer.framebuffer.buffer
+}
+
+// La luna tapa parte del lado iluminado del planeta; el sol, que contiene la luz, no tapa nada
+#[test]
+fn moons_eclipse_planets() {
+    let lit = render_eclipse("", false);
+    let eclipsed = render_eclipse("", true);
+    let darker = lit.iter().zip(&eclipsed).filter(|(a, b)| a != b).count();
+    assert!(darker > 20, "only {} pixels changed", darker);
+
+    assert_eq!(render_eclipse("cast_shadows = false", true), lit);
+}
@@ -0,0 +1,163 @@
+// skybox.rs
+
+use nalgebra_glm::Vec3;
+use std::path::Path;
+use std::sync::Arc;
+
+use graficas_lab4::{Body, Camera, Color, Obj, ProceduralSky, Renderer, Scene, ShaderRegistry, Skybox};
+use graficas_lab4::cubemap::{face_and_uv, face_direction};
+use graficas_lab4::noise::create_generic_noise;
+use graficas_lab4::scene_file::parse_scene;
+use graficas_lab4::skybox::{blackbody, StarfieldSettings};
+
+#[test]
+fn cube_face_coordinates_round_trip() {
+    for face in 0..6 {
+        for (u, v) in [(0.5, 0.5), (0.1, 0.8), (0.9, 0.25)] {
+            let (back, back_u, back_v) = face_and_uv(&face_direction(face, u, v));
+            assert_eq!(back, face);
+            assert!((back_u - u).abs() < 1e-5 && (back_v - v).abs() < 1e-5, "face {}: ({}, {})", face, back_u, back_v);
+        }
+    }
+    assert_eq!(face_and_uv(&Vec3::new(0.0, 0.0, -3.0)), (5, 0.5, 0.5));
+}
+
+fn all_stars(sky: &ProceduralSky) -> Vec<(usize, usize, usize)> {
+    let cells = sky.settings().cells;
+    (0..6 * cells * cells)
+        .map(|index| (index / (cells * cells), index % cells, index / cells % cells))
+        .filter(|&(face, x, y)| sky.star(face, x, y).is_some())
+        .collect()
+}
+
+#[test]
+fn the_seed_decides_the_stars() {
+    let settings = StarfieldSettings { cells: 32, ..StarfieldSettings::default() };
+    let sky = ProceduralSky::new(settings, None);
+    let stars = all_stars(&sky);
+
+    assert_eq!(stars, all_stars(&ProceduralSky::new(settings, None)));
+    assert_ne!(stars, all_stars(&ProceduralSky::new(StarfieldSettings { seed: 2, ..settings }, None)));
+
+    let fraction = stars.len() as f32 / (6 * 32 * 32) as f32;
+    assert!((fraction - settings.density).abs() < 0.03, "{} of the cells have a star", fraction);
+
+    // Cada estrella está dentro de su celda y en el rango de magnitudes
+    for &(face, x, y) in &stars {
+        let star = sky.star(face, x, y).unwrap();
+        let (star_face, u, v) = face_and_uv(&star.direction);
+        assert_eq!((star_face, (u * 32.0) as usize, (v * 32.0) as usize), (face, x, y));
+        assert!((0.0..=settings.faintest_magnitude).contains(&star.magnitude));
+    }
+}
+
+#[test]
+fn star_colors_and_twinkle() {
+    for kelvin in [3000.0, 6500.0, 10000.0] {
+        assert!((blackbody(kelvin).luminance() - 1.0).abs() < 1e-3);
+    }
+    let (red, blue) = (blackbody(3000.0), blackbody(10000.0));
+    assert!(red.r() > red.b() && blue.b() > blue.r());
+
+    let settings = StarfieldSettings { density: 1.0, ..StarfieldSettings::default() };
+    let sky = ProceduralSky::new(settings, None);
+    let star = sky.star(4, 10, 20).unwrap();
+    let brightness: Vec<f32> = (0..100).step_by(10).map(|time| star.brightness(&settings, time)).collect();
+    assert!(brightness.windows(2).any(|pair| pair[0] != pair[1]));
+
+    let steady = StarfieldSettings { twinkle: 0.0, ..settings };
+    assert_eq!(star.brightness(&steady, 0), star.brightness(&steady, 57));
+}
+
+// Cielo de prueba: rojo hacia -Z y verde hacia +Z, según la dirección de cada pixel
+struct Hemispheres;
+
+impl Skybox for Hemispheres {
+    fn sample(&self, direction: &Vec3, _pixel_angle: f32, _time: u32) -> Color {
+        if direction.z < 0.0 { Color::from_float(1.0, 0.0, 0.0) } else { Color::from_float(0.0, 1.0, 0.0) }
+    }
+}
+
+fn render_sky(scene: &Scene, eye: Vec3, center: Vec3) -> Vec<u32> {
+    let mut renderer = Renderer::new(80, 60);
+    renderer.raster.threads = 1;
+    renderer.clear();
+    renderer.set_camera(&Camera::new(eye, center, Vec3::new(0.0, 1.0, 0.0)));
+    renderer.draw_scene(scene);
+    renderer.framebuffer.buffer
+}
+
+#[test]
+fn the_sky_turns_with_the_camera_but_does_not_move_with_it() {
+    let mut scene = Scene::new();
+    scene.sky = Some(Box::new(Hemispheres));
+
+    let forward = render_sky(&scene, Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0));
+    assert!(forward.iter().all(|&pixel| pixel == 0xFF0000));
+    let backward = render_sky(&scene, Vec3::zeros(), Vec3::new(0.0, 0.0, 1.0));
+    assert!(backward.iter().all(|&pixel| pixel == 0x00FF00));
+
+    // Mirando hacia +X la mitad izquierda de la pantalla está hacia -Z
+    let sideways = render_sky(&scene, Vec3::zeros(), Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(sideways[30 * 80 + 10], 0xFF0000);
+    assert_eq!(sideways[30 * 80 + 70], 0x00FF00);
+
+    // Desplazar la cámara sin girarla no cambia el cielo
+    let starry = Scene { sky: Some(Box::new(ProceduralSky::new(StarfieldSettings::default(), None))), ..Scene::new() };
+    let here = render_sky(&starry, Vec3::zeros(), Vec3::new(0.3, 0.2, -1.0));
+    let there = render_sky(&starry, Vec3::new(5.0, -2.0, 7.0), Vec3::new(5.3, -1.8, 6.0));
+    // Solo cambia el redondeo de la matriz de vista invertida
+    let channels = |pixel: u32| [(pixel >> 16) & 0xFF, (pixel >> 8) & 0xFF, pixel & 0xFF];
+    for (a, b) in here.iter().zip(&there) {
+        assert!(channels(*a).iter().zip(channels(*b)).all(|(a, b)| a.abs_diff(b) <= 2), "{:06X} != {:06X}", a, b);
+    }
+    assert!(here.iter().any(|&pixel| pixel != 0));
+}
+
+#[test]
+fn bodies_hide_the_sky() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let mesh = Arc::new(Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array());
+    let mut scene = Scene::new();
+    scene.add(Body::new("planet", mesh, registry.create("rocky_planet").unwrap(), Arc::new(create_generic_noise()))).unwrap();
+
+    let eye = Vec3::new(0.0, 0.0, 3.0);
+    let plain = render_sky(&scene, eye, Vec3::zeros());
+    scene.sky = Some(Box::new(Hemispheres));
+    let with_sky = render_sky(&scene, eye, Vec3::zeros());
+
+    let center = 30 * 80 + 40;
+    assert_eq!(with_sky[center], plain[center]);
+    assert_eq!(with_sky[0], 0xFF0000);
+}
+
+#[test]
+fn scene_files_declare_the_sky() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let source = r##"
+        [sky]
+        seed = 3
+        star_density = 0.1
+
+        [sky.nebula]
+        colors = [[96, 40, 150], "#1e648c"]
+        coverage = 0.4
+        resolution = 16
+    "##;
+    let scene = parse_scene(source, Path::new("sky.toml"), &registry).unwrap().scene;
+    assert!(scene.sky.is_some());
+    assert!(parse_scene("", Path::new("empty.toml"), &registry).unwrap().scene.sky.is_none());
+
+    let cases = [
+        ("star_density = 2.0", "sky: star_density must be between 0 and 1, got 2"),
+        ("twinkle = -0.5", "sky: twinkle must be between 0 and 1, got -0.5"),
+        ("star_cells = 0", "sky: star_cells must be at least 1"),
+        ("[sky.nebula]\ncoverage = 1.5", "sky: nebula coverage must be between 0 and 1, got 1.5"),
+        ("[sky.nebula]\nnoise = \"fog\"", "sky: unknown noise preset 'fog'"),
+    ];
+    for (sky, expected) in cases {
+        let source = format!("[sky]\n{}\n", sky);
+        let error = parse_scene(&source, Path::new("sky.toml"), &registry).err().expect(sky).to_string();
+        assert!(error.starts_with(&format!("sky.toml: {}", expected)), "{}", error);
+    }
+}
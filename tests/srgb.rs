@@ -0,0 +1,44 @@
+// srgb.rs
+
+use graficas_lab4::color::{linear_to_srgb, srgb_to_linear};
+use graficas_lab4::Color;
+
+fn close(a: f32, b: f32) -> bool {
+    (a - b).abs() < 1e-3
+}
+
+#[test]
+fn transfer_functions_match_reference_values() {
+    assert!(close(srgb_to_linear(0.5), 0.2140));
+    assert!(close(linear_to_srgb(0.5), 0.7354));
+    assert_eq!(srgb_to_linear(0.0), 0.0);
+    assert!(close(srgb_to_linear(1.0), 1.0));
+    assert!(close(linear_to_srgb(srgb_to_linear(0.3)), 0.3));
+}
+
+#[test]
+fn byte_constructors_decode_srgb() {
+    assert!(close(Color::new(128, 128, 128).r(), 0.2158));
+    assert_eq!(Color::new(255, 0, 0), Color::from_hex(0xFF0000));
+    assert_eq!(Color::from_srgb(1.0, 1.0, 1.0).to_hex(), 0xFFFFFF);
+    assert_eq!(Color::from_linear(0.5, 0.5, 0.5), Color::from_float(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn every_byte_round_trips() {
+    for value in 0..=255u32 {
+        let hex = (value << 16) | ((255 - value) << 8) | value;
+        assert_eq!(Color::from_hex(hex).to_hex(), hex, "channel {}", value);
+    }
+}
+
+// Mezclar blanco y negro al 50% en lineal da un gris sRGB más claro que 0x808080
+#[test]
+fn blending_happens_in_linear_space() {
+    let mid = Color::black().lerp(&Color::from_hex(0xFFFFFF), 0.5);
+    assert_eq!(mid.to_hex(), 0xBCBCBC);
+    assert!(close(mid.to_srgb()[0], 0.7354));
+
+    let half = Color::from_hex(0xFFFFFF) * 0.5;
+    assert_eq!(half.to_hex(), 0xBCBCBC);
+}
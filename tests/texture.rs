@@ -0,0 +1,287 @@
+// texture.rs
+
+use nalgebra_glm::{Vec2, Vec3};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use graficas_lab4::{Camera, Color, CubeMap, Filter, ImageSky, Renderer, Sampler, Scene, ShaderRegistry, SkyImage, Texture, WrapMode};
+use graficas_lab4::framebuffer::Framebuffer;
+use graficas_lab4::scene_file::parse_scene;
+use graficas_lab4::texture::equirectangular_uv;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("graficas_texture_{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Imagen de 3x2 con colores distintos en cada pixel
+fn write_image(path: &Path) -> Vec<u32> {
+    let pixels = vec![0xFF0000, 0x00FF00, 0x0000FF, 0x102030, 0x808080, 0xFFFFFF];
+    let mut framebuffer = Framebuffer::new(3, 2);
+    framebuffer.buffer = pixels.clone();
+    framebuffer.save(path).unwrap();
+    pixels
+}
+
+#[test]
+fn png_and_ppm_files_load_as_srgb() {
+    let dir = temp_dir("load");
+    for name in ["image.png", "image.ppm"] {
+        let path = dir.join(name);
+        let pixels = write_image(&path);
+        let texture = Texture::load(&path).unwrap();
+        assert_eq!((texture.width(), texture.height()), (3, 2));
+        let loaded: Vec<u32> = (0..6).map(|i| texture.texel(i % 3, i / 3).to_hex()).collect();
+        assert_eq!(loaded, pixels, "{}", name);
+    }
+
+    // PPM de texto con comentarios
+    let ascii = dir.join("ascii.ppm");
+    fs::write(&ascii, "P3\n# dos pixeles\n2 1\n255\n255 0 0  0 0 255\n").unwrap();
+    let texture = Texture::load(&ascii).unwrap();
+    assert_eq!((texture.texel(0, 0).to_hex(), texture.texel(1, 0).to_hex()), (0xFF0000, 0x0000FF));
+
+    let missing = Texture::load(dir.join("missing.png")).unwrap_err().to_string();
+    assert!(missing.contains("missing.png"), "{}", missing);
+    fs::write(dir.join("broken.ppm"), "P6\n2 2\n255\n").unwrap();
+    assert!(Texture::load(dir.join("broken.ppm")).is_err());
+    // Medidas que desbordan el tamaño de la imagen son un error, no un pánico
+    for header in ["P6 4294967296 4294967296 255\n", "P3 4294967296 4294967296 255\n", "P6 6148914691236517205 1 255\n"] {
+        fs::write(dir.join("huge.ppm"), header).unwrap();
+        let error = Texture::load(dir.join("huge.ppm")).unwrap_err().to_string();
+        assert!(error.contains("too large"), "{}: {}", header, error);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Textura de 2x1: negro a la izquierda y blanco a la derecha
+fn black_and_white() -> Texture {
+    Texture::new(2, 1, vec![Color::black(), Color::from_float(1.0, 1.0, 1.0)]).unwrap()
+}
+
+#[test]
+fn nearest_and_bilinear_filtering() {
+    let texture = black_and_white();
+    let nearest = Sampler::new(Filter::Nearest, WrapMode::ClampToEdge);
+    let bilinear = Sampler::new(Filter::Bilinear, WrapMode::ClampToEdge);
+
+    assert_eq!(texture.sample(Vec2::new(0.49, 0.5), &nearest).r(), 0.0);
+    assert_eq!(texture.sample(Vec2::new(0.51, 0.5), &nearest).r(), 1.0);
+
+    // En el centro de cada texel el filtro bilineal da el texel exacto; entre ellos, la mezcla
+    assert_eq!(texture.sample(Vec2::new(0.25, 0.5), &bilinear).r(), 0.0);
+    assert_eq!(texture.sample(Vec2::new(0.75, 0.5), &bilinear).r(), 1.0);
+    assert!((texture.sample(Vec2::new(0.5, 0.5), &bilinear).r() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn wrap_modes() {
+    let texture = black_and_white();
+    let sample = |u: f32, wrap: WrapMode| texture.sample(Vec2::new(u, 0.5), &Sampler::new(Filter::Nearest, wrap)).r();
+
+    assert_eq!(sample(1.25, WrapMode::Repeat), 0.0);
+    assert_eq!(sample(-0.25, WrapMode::Repeat), 1.0);
+    assert_eq!(sample(1.25, WrapMode::MirroredRepeat), 1.0);
+    assert_eq!(sample(1.75, WrapMode::MirroredRepeat), 0.0);
+    assert_eq!(sample(-0.25, WrapMode::MirroredRepeat), 0.0);
+    assert_eq!(sample(3.0, WrapMode::ClampToEdge), 1.0);
+    assert_eq!(sample(-3.0, WrapMode::ClampToEdge), 0.0);
+
+    // Con Repeat el filtro bilineal mezcla el borde derecho con el izquierdo
+    let edge = texture.sample(Vec2::new(0.0, 0.5), &Sampler::new(Filter::Bilinear, WrapMode::Repeat));
+    assert!((edge.r() - 0.5).abs() < 1e-6);
+    assert_eq!("nearest".parse::<Filter>().unwrap(), Filter::Nearest);
+    assert!("anisotropic".parse::<Filter>().is_err());
+}
+
+#[test]
+fn textures_build_their_mip_chain() {
+    // Tablero de 8x4 blanco y negro con alfa alternado
+    let texture = Texture::from_fn(8, 4, |x, y| {
+        if (x + y) % 2 == 0 { Color::from_float(1.0, 1.0, 1.0) } else { Color::black().with_alpha(0.0) }
+    }).unwrap();
+    assert_eq!(texture.mip_levels(), 4);
+    let sizes: Vec<(usize, usize)> = (0..4).map(|level| texture.mip_size(level)).collect();
+    assert_eq!(sizes, [(8, 4), (4, 2), (2, 1), (1, 1)]);
+    for level in 1..4 {
+        let texel = texture.mip_texel(level, 0, 0);
+        assert!((texel.r() - 0.5).abs() < 1e-6 && (texel.a() - 0.5).abs() < 1e-6, "level {}: {}", level, texel);
+    }
+
+    // Los lados impares repiten el último texel
+    let odd = Texture::new(3, 1, vec![Color::black(), Color::black(), Color::from_float(1.0, 1.0, 1.0)]).unwrap();
+    assert_eq!(odd.mip_size(1), (1, 1));
+    assert_eq!(odd.mip_texel(1, 0, 0).r(), 0.0);
+}
+
+#[test]
+fn trilinear_filtering_follows_the_lod() {
+    let texture = Texture::from_fn(8, 8, |x, _| if x % 2 == 0 { Color::from_float(1.0, 1.0, 1.0) } else { Color::black() }).unwrap();
+    let trilinear = Sampler::new(Filter::Trilinear, WrapMode::Repeat);
+    let uv = Vec2::new(0.0625, 0.5);
+
+    assert_eq!(texture.sample_lod(uv, 0.0, &trilinear).r(), 1.0);
+    assert!((texture.sample_lod(uv, 1.0, &trilinear).r() - 0.5).abs() < 1e-6);
+    assert!((texture.sample_lod(uv, 0.5, &trilinear).r() - 0.75).abs() < 1e-6);
+    // Más allá del último nivel se usa el de 1x1
+    assert!((texture.sample_lod(uv, 10.0, &trilinear).r() - 0.5).abs() < 1e-6);
+    // Un LOD que no es finito lee el nivel 0, también en una textura de un solo nivel
+    assert_eq!(texture.sample_lod(uv, f32::NAN, &trilinear).r(), 1.0);
+    let single = Texture::from_fn(1, 1, |_, _| Color::from_float(0.25, 0.25, 0.25)).unwrap();
+    for lod in [f32::NAN, f32::INFINITY, 0.5] {
+        assert_eq!(single.sample_lod(uv, lod, &trilinear).r(), 0.25);
+    }
+    // Bilineal ignora los mipmaps
+    assert_eq!(texture.sample_lod(uv, 2.0, &Sampler::new(Filter::Bilinear, WrapMode::Repeat)).r(), 1.0);
+
+    // Un pixel que cubre 2 texels usa el nivel 1; uno que cubre menos de 1, el nivel 0
+    let step = 1.0 / 8.0;
+    let far = texture.sample_grad(uv, Vec2::new(2.0 * step, 0.0), Vec2::new(0.0, 2.0 * step), &trilinear);
+    assert!((far.r() - 0.5).abs() < 1e-6);
+    let near = texture.sample_grad(uv, Vec2::new(0.5 * step, 0.0), Vec2::new(0.0, 0.5 * step), &trilinear);
+    assert_eq!(near.r(), 1.0);
+}
+
+// Con un pixel alargado en v, el filtro anisotrópico conserva el detalle en u
+#[test]
+fn anisotropic_filtering_keeps_detail_across_the_short_axis() {
+    let texture = Texture::from_fn(16, 16, |x, _| if x % 2 == 0 { Color::from_float(1.0, 1.0, 1.0) } else { Color::black() }).unwrap();
+    let uv = Vec2::new(0.5 / 16.0, 0.5);
+    let (uv_dx, uv_dy) = (Vec2::new(1.0 / 16.0, 0.0), Vec2::new(0.0, 4.0 / 16.0));
+
+    let trilinear = Sampler::new(Filter::Trilinear, WrapMode::Repeat);
+    let isotropic = texture.sample_grad(uv, uv_dx, uv_dy, &trilinear);
+    let anisotropic = texture.sample_grad(uv, uv_dx, uv_dy, &trilinear.with_anisotropy(4));
+    assert!((isotropic.r() - 0.5).abs() < 1e-6);
+    assert_eq!(anisotropic.r(), 1.0);
+
+    // Con 2 muestras el LOD queda en 1, donde las franjas de 1 texel ya se promediaron
+    let capped = texture.sample_grad(uv, uv_dx, uv_dy, &trilinear.with_anisotropy(2));
+    assert!((capped.r() - 0.5).abs() < 1e-6);
+}
+
+#[test]
+fn directional_lookups() {
+    let close = |uv: Vec2, u: f32, v: f32| (uv.x - u).abs() < 1e-6 && (uv.y - v).abs() < 1e-6;
+    assert!(close(equirectangular_uv(&Vec3::new(0.0, 0.0, -1.0)), 0.5, 0.5));
+    assert!(close(equirectangular_uv(&Vec3::new(2.0, 0.0, 0.0)), 0.75, 0.5));
+    // En los polos u no importa
+    assert_eq!(equirectangular_uv(&Vec3::new(0.0, 1.0, 0.0)).y, 0.0);
+    assert_eq!(equirectangular_uv(&Vec3::new(0.0, -1.0, 0.0)).y, 1.0);
+
+    // Cada cara de un color: la dirección elige la cara
+    let colors = [0xFF0000, 0x00FF00, 0x0000FF, 0xFFFF00, 0xFF00FF, 0x00FFFF];
+    let cube_map = CubeMap::new(colors.map(|hex| Texture::new(1, 1, vec![Color::from_hex(hex)]).unwrap())).unwrap();
+    let axes = [Vec3::x(), -Vec3::x(), Vec3::y(), -Vec3::y(), Vec3::z(), -Vec3::z()];
+    for (axis, hex) in axes.iter().zip(colors) {
+        assert_eq!(cube_map.sample(&(axis * 3.0), &Sampler::default()).to_hex(), hex);
+    }
+}
+
+#[test]
+fn cube_map_faces_must_be_square_and_the_same_size() {
+    let dir = temp_dir("cube");
+    let mut paths = Vec::new();
+    for (index, (width, height)) in [(2, 2), (2, 2), (2, 2), (2, 2), (4, 4), (2, 2)].into_iter().enumerate() {
+        let path = dir.join(format!("face{}.ppm", index));
+        Framebuffer::new(width, height).save(&path).unwrap();
+        paths.push(path);
+    }
+    let paths: [PathBuf; 6] = paths.try_into().unwrap();
+    let error = CubeMap::load(&paths).unwrap_err().to_string();
+    assert!(error.contains("face4.ppm") && error.contains("cube map face +Z is 4x4, but every face must be 2x2"), "{}", error);
+
+    Framebuffer::new(2, 1).save(&paths[4]).unwrap();
+    let error = CubeMap::load(&paths).unwrap_err().to_string();
+    assert!(error.contains("+Z is 2x1"), "{}", error);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cube_maps_reject_faces_of_different_sizes() {
+    let face = |width, height| Texture::new(width, height, vec![Color::black(); width * height]).unwrap();
+    let error = CubeMap::new([face(1, 1), face(1, 1), face(1, 1), face(1, 2), face(1, 1), face(1, 1)]).unwrap_err();
+    assert_eq!(error.to_string(), "cube map face -Y is 1x2, but every face must be 1x1");
+}
+
+#[test]
+fn textures_reject_empty_sizes_and_wrong_texel_counts() {
+    let error = Texture::new(0, 4, Vec::new()).unwrap_err();
+    assert_eq!(error.to_string(), "textures need at least one texel");
+    let error = Texture::new(2, 2, vec![Color::black(); 3]).unwrap_err();
+    assert_eq!(error.to_string(), "a 2x2 texture needs 4 texels, got 3");
+    assert!(Texture::from_fn(0, 0, |_, _| Color::black()).is_err());
+}
+
+fn render(scene: &Scene) -> Vec<u32> {
+    let mut renderer = Renderer::new(80, 60);
+    renderer.raster.threads = 1;
+    renderer.clear();
+    renderer.set_camera(&Camera::new(Vec3::zeros(), Vec3::new(0.0, 0.0, -1.0), Vec3::new(0.0, 1.0, 0.0)));
+    renderer.draw_scene(scene);
+    renderer.framebuffer.buffer
+}
+
+#[test]
+fn image_skies_fill_the_background() {
+    // Mapa equirectangular con la mitad de arriba roja y la de abajo azul
+    let texture = Texture::from_fn(16, 8, |_, y| if y < 4 { Color::from_hex(0xFF0000) } else { Color::from_hex(0x0000FF) }).unwrap();
+    let mut sky = ImageSky::new(SkyImage::Equirectangular(texture));
+    sky.sampler.filter = Filter::Nearest;
+    let scene = Scene { sky: Some(Box::new(sky)), ..Scene::new() };
+
+    let pixels = render(&scene);
+    assert_eq!(pixels[5 * 80 + 40], 0xFF0000);
+    assert_eq!(pixels[55 * 80 + 40], 0x0000FF);
+}
+
+#[test]
+fn scene_files_attach_textures() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let dir = temp_dir("scene");
+    let image = dir.join("surface.png");
+    write_image(&image);
+
+    let source = format!(
+        r#"
+        [sky]
+        image = "{image}"
+        intensity = 2.0
+        filter = "nearest"
+
+        [[body]]
+        name = "earth"
+        mesh = "assets/model/sphere.obj"
+        shader = "textured"
+        textures = {{ surface = "{image}" }}
+
+        [[body]]
+        name = "mars"
+        mesh = "assets/model/sphere.obj"
+        shader = "textured"
+        textures = {{ surface = "{image}" }}
+        "#,
+        image = image.display()
+    );
+    let scene = parse_scene(&source, Path::new("textured.toml"), &registry).unwrap().scene;
+    assert!(scene.sky.is_some());
+    let earth = scene.bodies[0].textures.get("surface").unwrap();
+    assert_eq!(earth.texel(2, 0).to_hex(), 0x0000FF);
+    // El mismo archivo se carga una sola vez
+    assert!(std::sync::Arc::ptr_eq(earth, scene.bodies[1].textures.get("surface").unwrap()));
+
+    let missing = dir.join("missing.png");
+    let cases = [
+        (format!("[sky]\nimage = \"{}\"\ncube = [\"a\", \"b\", \"c\", \"d\", \"e\", \"f\"]", image.display()), "sky: use either image or cube, not both"),
+        (format!("[sky]\nimage = \"{}\"\nseed = 4", image.display()), "sky: star and nebula settings only apply to procedural skies"),
+        (format!("[sky]\nimage = \"{}\"\nintensity = -1.0", image.display()), "sky: intensity must not be negative, got -1"),
+        ("[sky]\nfilter = \"nearest\"".to_string(), "sky: intensity and filter only apply to image skies"),
+        (format!("[[body]]\nname = \"earth\"\nmesh = \"assets/model/sphere.obj\"\nshader = \"textured\"\ntextures = {{ surface = \"{}\" }}", missing.display()), "failed to load texture 'surface'"),
+    ];
+    for (source, expected) in cases {
+        let error = parse_scene(&source, Path::new("textured.toml"), &registry).err().expect(&source).to_string();
+        assert!(error.contains(expected), "{}", error);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
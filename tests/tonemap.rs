@@ -0,0 +1,79 @@
+// tonemap.rs
+
+use nalgebra_glm::Vec3;
+use std::sync::Arc;
+
+use graficas_lab4::{Camera, Color, Obj, Renderer, ShaderRegistry, ToneMapping};
+use graficas_lab4::framebuffer::Framebuffer;
+use graficas_lab4::matrix::create_model_matrix;
+use graficas_lab4::noise::create_generic_noise;
+
+#[test]
+fn color_arithmetic_does_not_saturate() {
+    let glow = Color::new(240, 230, 40) + Color::new(240, 230, 40);
+    assert!(glow.r() > 1.0 && glow.g() > 1.0);
+    assert_eq!((glow * 0.5).to_hex(), 0xF0E628);
+    assert_eq!(Color::from_hex(0x336699).to_hex(), 0x336699);
+}
+
+#[test]
+fn tone_mappers_stay_in_range_and_preserve_order() {
+    for mode in ToneMapping::ALL {
+        let mut previous = -1.0;
+        for i in 0..=100 {
+            let value = i as f32 * 0.2;
+            let mapped = mode.apply(Color::from_float(value, value, value), 1.0).r();
+            assert!((0.0..=1.0).contains(&mapped), "{} {}", mode, value);
+            assert!(mapped >= previous, "{} is not monotonic at {}", mode, value);
+            previous = mapped;
+        }
+        assert_eq!(mode.apply(Color::black(), 1.0), Color::black());
+    }
+
+    let white = Color::from_float(1.0, 1.0, 1.0);
+    assert_eq!(ToneMapping::Clamp.apply(Color::from_float(3.0, 0.5, 1.0), 1.0), Color::from_float(1.0, 0.5, 1.0));
+    assert_eq!(ToneMapping::Reinhard.apply(white, 1.0).r(), 0.5);
+    assert!((ToneMapping::Exposure.apply(white, 2.0).r() - (1.0 - (-2.0f32).exp())).abs() < 1e-6);
+    assert!((ToneMapping::Aces.apply(white, 1.0).r() - 0.8038).abs() < 1e-3);
+}
+
+#[test]
+fn resolve_applies_exposure_and_tone_mapping() {
+    let mut framebuffer = Framebuffer::new(1, 1);
+    framebuffer.clear();
+    framebuffer.set_current_color(0xFFFFFF);
+    framebuffer.point(0, 0, 0.5);
+
+    framebuffer.resolve();
+    assert_eq!(framebuffer.buffer[0], 0xFFFFFF);
+
+    // 0.5 lineal se codifica como 0xBC en sRGB
+    framebuffer.tone_mapping = ToneMapping::Reinhard;
+    framebuffer.resolve();
+    assert_eq!(framebuffer.buffer[0], 0xBCBCBC);
+
+    framebuffer.tone_mapping = ToneMapping::Clamp;
+    framebuffer.exposure = 0.5;
+    framebuffer.resolve();
+    assert_eq!(framebuffer.buffer[0], 0xBCBCBC);
+}
+
+// El brillo del sol pasa de 1.0: con la exposición a la mitad sigue habiendo
+// píxeles más brillantes que el gris medio, en lugar de un amarillo plano recortado
+#[test]
+fn sun_glow_keeps_highlights_above_one() {
+    let registry = ShaderRegistry::with_builtin_shaders();
+    let shader = registry.create("sun").unwrap();
+    let mesh = Obj::load("assets/model/sphere.obj").unwrap().get_vertex_array();
+
+    let mut renderer = Renderer::new(160, 120);
+    renderer.framebuffer.exposure = 0.5;
+    renderer.clear();
+    renderer.set_time(42);
+    renderer.set_camera(&Camera::new(Vec3::new(0.0, 0.0, 3.0), Vec3::new(0.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)));
+    let model_matrix = create_model_matrix(Vec3::new(0.0, 0.0, 0.0), 1.0, Vec3::new(0.0, 0.0, 0.0));
+    renderer.draw(&mesh, model_matrix, &Arc::new(create_generic_noise()), shader.as_ref());
+
+    let brightest = renderer.framebuffer.buffer.iter().map(|pixel| (pixel >> 16) & 0xFF).max().unwrap();
+    assert!(brightest > 0x80, "brightest red channel {:#x}", brightest);
+}
@@ -0,0 +1,71 @@
+// varying.rs
+
+use nalgebra_glm::{Vec2, Vec3, Vec4};
+
+use graficas_lab4::{Color, Varyings, Vertex};
+use graficas_lab4::clipping::clip_triangle;
+use graficas_lab4::triangle::{triangle, Interpolation};
+use graficas_lab4::varying::MAX_VARYINGS;
+
+#[test]
+fn varyings_are_stored_by_location() {
+    let mut varyings = Varyings::new();
+    assert!(varyings.is_empty());
+
+    varyings.set_float(0, 0.5);
+    varyings.set_vec3(1, Vec3::new(1.0, 2.0, 3.0));
+    varyings.set_color(6, Color::from_float(0.2, 0.4, 0.6).with_alpha(0.5));
+    varyings.set_float(0, 0.75);
+
+    assert_eq!(varyings.len(), 10);
+    assert_eq!(varyings.float(0), 0.75);
+    assert_eq!(varyings.vec3(1), Vec3::new(1.0, 2.0, 3.0));
+    assert_eq!(varyings.vec2(2), Vec2::new(2.0, 3.0));
+    assert_eq!(varyings.color(6).a(), 0.5);
+    // Lo que nadie escribió vale 0
+    assert_eq!(varyings.float(4), 0.0);
+}
+
+#[test]
+#[should_panic(expected = "varyings only have")]
+fn varyings_have_a_fixed_capacity() {
+    Varyings::new().set_vec3(MAX_VARYINGS - 2, Vec3::zeros());
+}
+
+// Mismo triángulo que en interpolation.rs: el vértice `b` está 4 veces más lejos
+fn screen_vertex(x: f32, y: f32, w: f32, u: f32) -> Vertex {
+    let mut vertex = Vertex::new(Vec3::zeros(), Vec3::new(0.0, 0.0, 1.0), Vec2::new(u, 1.0 - u));
+    vertex.clip_position = Vec4::new(0.0, 0.0, 0.0, w);
+    vertex.transformed_position = Vec3::new(x, y, 0.5);
+    vertex.varyings.set_float(0, u);
+    vertex.varyings.set_vec2(1, vertex.tex_coords);
+    vertex
+}
+
+#[test]
+fn varyings_are_interpolated_like_tex_coords() {
+    let (a, b, c) = (screen_vertex(0.0, 0.0, 1.0, 0.0), screen_vertex(64.0, 0.0, 4.0, 1.0), screen_vertex(0.0, 64.0, 1.0, 0.0));
+    for interpolation in [Interpolation::Perspective, Interpolation::Affine] {
+        let fragments = triangle(&a, &b, &c, interpolation);
+        assert!(!fragments.is_empty());
+        for fragment in fragments {
+            assert!((fragment.varyings.float(0) - fragment.tex_coords.x).abs() < 1e-5);
+            assert!((fragment.varyings.vec2(1) - fragment.tex_coords).norm() < 1e-5);
+        }
+    }
+}
+
+#[test]
+fn clipping_interpolates_varyings() {
+    let vertex = |x: f32, z: f32, w: f32, value: f32| {
+        let mut vertex = Vertex::new(Vec3::new(x, 0.0, z), Vec3::new(0.0, 0.0, 1.0), Vec2::zeros());
+        vertex.clip_position = Vec4::new(x, 0.0, z, w);
+        vertex.varyings.set_float(0, value);
+        vertex
+    };
+    // Un vértice detrás del plano cercano: los nuevos vértices quedan entre valores 0 y 1
+    let triangles = clip_triangle(&vertex(-0.5, 0.0, 1.0, 0.0), &vertex(0.5, 0.0, 1.0, 0.0), &vertex(0.0, -3.0, 1.0, 1.0));
+    let values: Vec<f32> = triangles.iter().flatten().map(|v| v.varyings.float(0)).collect();
+    assert!(values.iter().any(|&value| value > 0.0 && value < 1.0), "{:?}", values);
+    assert!(values.iter().all(|&value| (0.0..1.0).contains(&value)));
+}
@@ -0,0 +1,31 @@
+// watcher.rs
+
+use std::fs::{self, File};
+use std::time::{Duration, SystemTime};
+
+use graficas_lab4::watcher::FileWatcher;
+
+#[test]
+fn reports_each_modification_once() {
+    let dir = std::env::temp_dir().join(format!("graficas_watcher_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("params.toml");
+    fs::write(&path, "a = 1\n").unwrap();
+
+    let mut watcher = FileWatcher::new();
+    watcher.watch(&path);
+    assert!(watcher.changed().is_empty());
+
+    fs::write(&path, "a = 2\n").unwrap();
+    let later = SystemTime::now() + Duration::from_secs(5);
+    File::options().write(true).open(&path).unwrap().set_modified(later).unwrap();
+
+    assert_eq!(watcher.changed(), vec![path.clone()]);
+    assert!(watcher.changed().is_empty());
+
+    // Mientras el archivo no existe no se informa ningún cambio
+    fs::remove_file(&path).unwrap();
+    assert!(watcher.changed().is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}